@@ -0,0 +1,325 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::time::SystemTime;
+
+#[cfg(feature = "audio")]
+use macroquad::audio::{Sound, load_sound};
+use macroquad::prelude::*;
+use macroquad::ui::Skin;
+
+use crate::atlas::{Atlas, Sprite};
+
+const PLACEHOLDER_SIZE: u16 = 16; // Width and height of the placeholder texture
+const PLACEHOLDER_CELL: u16 = 4; // Width of the squares of the placeholder checkerboard
+const WATCH_INTERVAL: f32 = 0.5; // Seconds between two checks of the watched files
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TextureHandle(usize);
+
+/// Image packed in the texture atlas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SpriteHandle(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct FontHandle(usize);
+
+#[cfg(feature = "audio")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SoundHandle(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SkinHandle(usize);
+
+/// Builds a skin from its font, the default font being used when it is `None`
+type SkinBuilder = fn(Option<&Font>) -> Skin;
+
+/// Asset waiting to be loaded, with the slot it will fill
+enum PendingAsset {
+    Texture(TextureHandle),
+    Sprite(SpriteHandle),
+    Font(FontHandle),
+    #[cfg(feature = "audio")]
+    Sound(SoundHandle),
+}
+
+/// Loads every asset once by id (its path) and shares it through handles.
+/// Assets are queued first, then loaded one at a time so a loading screen can be drawn meanwhile.
+/// Sprites are packed in an atlas built once every queued asset is loaded, the other textures
+/// (e.g. the background) stay apart.
+/// A texture that can't be loaded is replaced by a placeholder, a font by the default font
+/// and a sound by silence. Sounds need the `audio` feature.
+pub(crate) struct AssetManager {
+    textures: Vec<Texture2D>,
+    texture_ids: HashMap<String, TextureHandle>,
+    /// Images of the sprites, kept to build the atlas again, with whether they get pre-scaled versions
+    sprite_images: Vec<(Image, bool)>,
+    sprite_ids: HashMap<String, SpriteHandle>,
+    atlas: Atlas,
+    /// Set when a sprite image changed since the atlas was built
+    atlas_outdated: bool,
+    fonts: Vec<Option<Font>>,
+    font_ids: HashMap<String, FontHandle>,
+    #[cfg(feature = "audio")]
+    sounds: Vec<Option<Sound>>,
+    #[cfg(feature = "audio")]
+    sound_ids: HashMap<String, SoundHandle>,
+    /// Skins with the font they use and the function building them, to rebuild them when the font is reloaded
+    skins: Vec<(Skin, FontHandle, SkinBuilder)>,
+    placeholder: Image,
+    pending: VecDeque<(String, PendingAsset)>,
+    nb_queued: usize,
+    /// Last modification time of the files checked for hot reload
+    watched: HashMap<String, Option<SystemTime>>,
+    watch_timer: f32,
+}
+
+impl AssetManager {
+    pub(crate) fn new() -> Self {
+        Self {
+            textures: Vec::new(),
+            texture_ids: HashMap::new(),
+            sprite_images: Vec::new(),
+            sprite_ids: HashMap::new(),
+            atlas: Atlas::build(&[]),
+            atlas_outdated: false,
+            fonts: Vec::new(),
+            font_ids: HashMap::new(),
+            #[cfg(feature = "audio")]
+            sounds: Vec::new(),
+            #[cfg(feature = "audio")]
+            sound_ids: HashMap::new(),
+            skins: Vec::new(),
+            placeholder: placeholder_image(),
+            pending: VecDeque::new(),
+            nb_queued: 0,
+            watched: HashMap::new(),
+            watch_timer: 0.,
+        }
+    }
+
+    /// Handle of the texture, which shows the placeholder until it is loaded
+    pub(crate) fn queue_texture(&mut self, path: &str) -> TextureHandle {
+        if let Some(handle) = self.texture_ids.get(path) {
+            return *handle;
+        }
+        let handle = TextureHandle(self.textures.len());
+        let placeholder = Texture2D::from_image(&self.placeholder);
+        placeholder.set_filter(FilterMode::Nearest);
+        self.textures.push(placeholder);
+        self.texture_ids.insert(path.to_string(), handle);
+        self.queue(path, PendingAsset::Texture(handle));
+        handle
+    }
+
+    /// Handle of an image of the atlas, which shows the placeholder until it is loaded.
+    /// `mips` adds pre-scaled versions of the image, for art drawn much smaller than its png.
+    pub(crate) fn queue_sprite(&mut self, path: &str, mips: bool) -> SpriteHandle {
+        if let Some(handle) = self.sprite_ids.get(path).copied() {
+            if mips && !self.sprite_images[handle.0].1 {
+                self.sprite_images[handle.0].1 = true;
+                self.atlas_outdated = true;
+            }
+            return handle;
+        }
+        let handle = SpriteHandle(self.sprite_images.len());
+        self.sprite_images.push((self.placeholder.clone(), mips));
+        self.sprite_ids.insert(path.to_string(), handle);
+        self.atlas_outdated = true;
+        self.queue(path, PendingAsset::Sprite(handle));
+        handle
+    }
+
+    pub(crate) fn queue_font(&mut self, path: &str) -> FontHandle {
+        if let Some(handle) = self.font_ids.get(path) {
+            return *handle;
+        }
+        let handle = FontHandle(self.fonts.len());
+        self.fonts.push(None);
+        self.font_ids.insert(path.to_string(), handle);
+        self.queue(path, PendingAsset::Font(handle));
+        handle
+    }
+
+    #[cfg(feature = "audio")]
+    #[allow(dead_code)] // No sound is played yet
+    pub(crate) fn queue_sound(&mut self, path: &str) -> SoundHandle {
+        if let Some(handle) = self.sound_ids.get(path) {
+            return *handle;
+        }
+        let handle = SoundHandle(self.sounds.len());
+        self.sounds.push(None);
+        self.sound_ids.insert(path.to_string(), handle);
+        self.queue(path, PendingAsset::Sound(handle));
+        handle
+    }
+
+    /// Skins are built from the loaded fonts, so they are added once the loading is done
+    pub(crate) fn add_skin(&mut self, font: FontHandle, build: SkinBuilder) -> SkinHandle {
+        self.skins.push((build(self.font(font)), font, build));
+        SkinHandle(self.skins.len() - 1)
+    }
+
+    fn queue(&mut self, path: &str, asset: PendingAsset) {
+        self.pending.push_back((path.to_string(), asset));
+        self.nb_queued += 1;
+        self.watch(path);
+    }
+
+    /// Check the file for modifications in `changed_files`. Queued assets are already watched.
+    pub(crate) fn watch(&mut self, path: &str) {
+        self.watched.insert(path.to_string(), modification_time(path));
+    }
+
+    pub(crate) fn is_loaded(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Fraction of the queued assets already loaded, from 0 to 1
+    pub(crate) fn progress(&self) -> f32 {
+        if self.nb_queued == 0 {
+            return 1.;
+        }
+        1. - self.pending.len() as f32 / self.nb_queued as f32
+    }
+
+    /// Load the next queued asset, logging an error when it can't be loaded
+    pub(crate) async fn load_next(&mut self) {
+        let Some((path, asset)) = self.pending.pop_front() else {
+            return;
+        };
+        match asset {
+            PendingAsset::Texture(TextureHandle(index)) => match load_texture(&path).await {
+                Ok(texture) => self.textures[index] = smoothed(texture),
+                Err(error) => println!("Failed to load texture {path}, using a placeholder: {error}"),
+            },
+            PendingAsset::Sprite(SpriteHandle(index)) => match self.load_sprite_image(&path).await {
+                Ok(image) => self.sprite_images[index].0 = image,
+                Err(error) => println!("{error}, using a placeholder"),
+            },
+            PendingAsset::Font(FontHandle(index)) => match load_ttf_font(&path).await {
+                Ok(font) => self.fonts[index] = Some(font),
+                Err(error) => println!("Failed to load font {path}, using the default font: {error}"),
+            },
+            #[cfg(feature = "audio")]
+            PendingAsset::Sound(SoundHandle(index)) => match load_sound(&path).await {
+                Ok(sound) => self.sounds[index] = Some(sound),
+                Err(error) => println!("Failed to load sound {path}, it won't be played: {error}"),
+            },
+        }
+        if self.is_loaded() && self.atlas_outdated {
+            self.build_atlas();
+        }
+    }
+
+    async fn load_sprite_image(&self, path: &str) -> Result<Image, String> {
+        let image = load_image(path).await.map_err(|error| format!("Failed to load sprite {path}: {error}"))?;
+        if image.width > Atlas::max_sprite_width() {
+            return Err(format!("Sprite {path} is {} px wide, more than the {} px of the atlas",
+                image.width, Atlas::max_sprite_width()));
+        }
+        Ok(image)
+    }
+
+    fn build_atlas(&mut self) {
+        self.atlas = Atlas::build(&self.sprite_images);
+        self.atlas_outdated = false;
+    }
+
+    /// Load every queued asset at once, for assets queued once the loading screen is over
+    pub(crate) async fn load_pending(&mut self) {
+        while !self.is_loaded() {
+            self.load_next().await;
+        }
+        if self.atlas_outdated {
+            self.build_atlas();
+        }
+    }
+
+    /// Watched files modified since the last call, checked every `WATCH_INTERVAL` seconds
+    pub(crate) fn changed_files(&mut self) -> Vec<String> {
+        self.watch_timer -= get_frame_time();
+        if self.watch_timer > 0. {
+            return Vec::new();
+        }
+        self.watch_timer = WATCH_INTERVAL;
+        let mut changed_files = Vec::new();
+        for (path, last_modification) in self.watched.iter_mut() {
+            let modification = modification_time(path);
+            if modification != *last_modification {
+                *last_modification = modification;
+                changed_files.push(path.clone());
+            }
+        }
+        changed_files
+    }
+
+    /// Load again a texture or a font from its file, keeping the current version when it fails.
+    /// Files which aren't textures or fonts of the manager are ignored.
+    pub(crate) async fn reload(&mut self, path: &str) -> Result<(), String> {
+        if let Some(TextureHandle(index)) = self.texture_ids.get(path).copied() {
+            let texture = load_texture(path).await.map_err(|error| format!("Failed to reload {path}: {error}"))?;
+            self.textures[index] = smoothed(texture);
+        } else if let Some(SpriteHandle(index)) = self.sprite_ids.get(path).copied() {
+            self.sprite_images[index].0 = self.load_sprite_image(path).await?;
+            self.build_atlas();
+        } else if let Some(handle) = self.font_ids.get(path).copied() {
+            let font = load_ttf_font(path).await.map_err(|error| format!("Failed to reload {path}: {error}"))?;
+            self.fonts[handle.0] = Some(font);
+            for index in 0..self.skins.len() {
+                let (_, font, build) = self.skins[index];
+                if font == handle {
+                    self.skins[index].0 = build(self.font(font));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn texture(&self, handle: TextureHandle) -> &Texture2D {
+        &self.textures[handle.0]
+    }
+
+    pub(crate) fn sprite(&self, handle: SpriteHandle) -> Sprite<'_> {
+        self.atlas.sprite(handle.0)
+    }
+
+    /// `None` when the font couldn't be loaded
+    pub(crate) fn font(&self, handle: FontHandle) -> Option<&Font> {
+        self.fonts[handle.0].as_ref()
+    }
+
+    /// `None` when the sound couldn't be loaded
+    #[cfg(feature = "audio")]
+    #[allow(dead_code)] // No sound is played yet
+    pub(crate) fn sound(&self, handle: SoundHandle) -> Option<&Sound> {
+        self.sounds[handle.0].as_ref()
+    }
+
+    pub(crate) fn skin(&self, handle: SkinHandle) -> &Skin {
+        &self.skins[handle.0].0
+    }
+}
+
+/// Textures are smoothed whatever the default filter mode is, as the weapon pngs are scaled down a lot
+fn smoothed(texture: Texture2D) -> Texture2D {
+    texture.set_filter(FilterMode::Linear);
+    texture
+}
+
+/// `None` when the file can't be read, e.g. on the web
+fn modification_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Magenta and black checkerboard, easy to spot in game
+fn placeholder_image() -> Image {
+    let mut image = Image::gen_image_color(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE, BLACK);
+    for x in 0..PLACEHOLDER_SIZE {
+        for y in 0..PLACEHOLDER_SIZE {
+            if (x / PLACEHOLDER_CELL + y / PLACEHOLDER_CELL).is_multiple_of(2) {
+                image.set_pixel(x as u32, y as u32, MAGENTA);
+            }
+        }
+    }
+    image
+}
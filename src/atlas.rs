@@ -0,0 +1,125 @@
+use macroquad::prelude::*;
+
+const ATLAS_WIDTH: u16 = 2048;
+const PADDING: u16 = 2; // Transparent pixels around each sprite, so linear filtering doesn't bleed between them
+const MIN_MIP_WIDTH: u16 = 16; // Pre-scaled versions stop before getting narrower than this
+
+/// Sprites packed in one texture. Macroquad batches the consecutive draws using the same texture,
+/// so drawing sprites of the atlas in a row costs a single draw call.
+pub(crate) struct Atlas {
+    pub(crate) texture: Texture2D,
+    /// Regions of each sprite in the texture, from the full size to the smallest pre-scaled version
+    regions: Vec<Vec<Rect>>,
+}
+
+/// A sprite of the atlas
+pub(crate) struct Sprite<'a> {
+    pub(crate) texture: &'a Texture2D,
+    levels: &'a [Rect],
+}
+
+impl Atlas {
+    /// Pack the images, each one with its pre-scaled versions (half the size of the previous one) when
+    /// `mips` is set, for art drawn much smaller than its png. Images must fit in `max_sprite_width`.
+    pub(crate) fn build(images: &[(Image, bool)]) -> Self {
+        // Every version of every image, as (sprite index, image)
+        let mut versions: Vec<(usize, Image)> = Vec::new();
+        for (index, (image, mips)) in images.iter().enumerate() {
+            let mut level = image.clone();
+            while *mips && level.width / 2 >= MIN_MIP_WIDTH && level.height / 2 >= 1 {
+                let next_level = half_size(&level);
+                versions.push((index, level));
+                level = next_level;
+            }
+            versions.push((index, level));
+        }
+
+        // Shelf packing: the versions are sorted by height then put side by side in rows
+        let mut order: Vec<usize> = (0..versions.len()).collect();
+        order.sort_by_key(|i| std::cmp::Reverse(versions[*i].1.height));
+        let mut positions = vec![(0u16, 0u16); versions.len()];
+        let (mut x, mut y, mut row_height) = (0, 0, 0);
+        for i in order.iter() {
+            let image = &versions[*i].1;
+            if x + image.width + 2 * PADDING > ATLAS_WIDTH {
+                x = 0;
+                y += row_height;
+                row_height = 0;
+            }
+            positions[*i] = (x + PADDING, y + PADDING);
+            x += image.width + 2 * PADDING;
+            row_height = row_height.max(image.height + 2 * PADDING);
+        }
+        let height = (y + row_height).max(1);
+
+        let mut atlas_image = Image::gen_image_color(ATLAS_WIDTH, height, BLANK);
+        let mut regions = vec![Vec::new(); images.len()];
+        // `versions` lists the levels of a sprite from the largest to the smallest
+        for ((index, image), (x, y)) in versions.iter().zip(positions) {
+            copy_image(image, &mut atlas_image, x, y);
+            regions[*index].push(Rect::new(x as f32, y as f32, image.width as f32, image.height as f32));
+        }
+        let texture = Texture2D::from_image(&atlas_image);
+        texture.set_filter(FilterMode::Linear);
+        Self { texture, regions }
+    }
+
+    /// Width of the widest image which can be packed
+    pub(crate) fn max_sprite_width() -> u16 {
+        ATLAS_WIDTH - 2 * PADDING
+    }
+
+    pub(crate) fn sprite(&self, index: usize) -> Sprite<'_> {
+        Sprite { texture: &self.texture, levels: &self.regions[index] }
+    }
+}
+
+impl Sprite<'_> {
+    /// Region of the full size sprite in the atlas
+    pub(crate) fn region(&self) -> Rect {
+        self.levels[0]
+    }
+
+    pub(crate) fn size(&self) -> Vec2 {
+        self.levels[0].size()
+    }
+
+    /// Smallest version of the sprite at least as wide as it is drawn, sharp without aliasing
+    pub(crate) fn region_for_width(&self, drawn_width: f32) -> Rect {
+        self.levels.iter().rev()
+            .find(|region| region.w >= drawn_width)
+            .copied()
+            .unwrap_or(self.levels[0])
+    }
+}
+
+/// Image half the size of the given one, each pixel being the average of 4 pixels
+fn half_size(image: &Image) -> Image {
+    let (width, height) = (image.width / 2, image.height / 2);
+    let mut half = Image::gen_image_color(width, height, BLANK);
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let mut sum = [0u32; 4];
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let source = ((2 * y + dy) * image.width as usize + 2 * x + dx) * 4;
+                for (channel, total) in sum.iter_mut().enumerate() {
+                    *total += image.bytes[source + channel] as u32;
+                }
+            }
+            let destination = (y * width as usize + x) * 4;
+            for (channel, total) in sum.iter().enumerate() {
+                half.bytes[destination + channel] = (total / 4) as u8;
+            }
+        }
+    }
+    half
+}
+
+fn copy_image(source: &Image, destination: &mut Image, x: u16, y: u16) {
+    let row_length = source.width as usize * 4;
+    for row in 0..source.height as usize {
+        let from = row * row_length;
+        let to = ((y as usize + row) * destination.width as usize + x as usize) * 4;
+        destination.bytes[to..to + row_length].copy_from_slice(&source.bytes[from..from + row_length]);
+    }
+}
@@ -0,0 +1,117 @@
+use macroquad::models::{Mesh, Vertex, draw_mesh};
+use macroquad::prelude::*;
+
+use crate::damage::DamageEvent;
+use crate::settings::Settings;
+
+const MAX_SHAKE_OFFSET: f32 = 16.; // Pixels the view moves at full trauma
+const SHAKE_FREQUENCY: f32 = 30.; // How fast the view moves while shaking
+const TRAUMA_DECAY: f32 = 1.5; // Trauma lost each second
+const PLAYER_HIT_TRAUMA: f32 = 0.5;
+const BIG_KILL_TRAUMA: f32 = 0.3;
+const BIG_KILL_MIN_HP: f32 = 8.; // Max HP from which killing an ennemy shakes the view
+const BOSS_IMPACT_TRAUMA: f32 = 0.4;
+const DAMAGE_FLASH_DURATION: f32 = 0.3;
+const DAMAGE_FLASH_COLOR: Color = Color::new(0.9, 0., 0., 0.5);
+const LOW_HP_RATIO: f32 = 0.3; // Fraction of the max HP under which the low HP vignette pulses
+const LOW_HP_COLOR: Color = Color::new(0.6, 0., 0., 0.35);
+const LOW_HP_PULSE_SPEED: f32 = 4.; // Radians per second
+const VIGNETTE_WIDTH: f32 = 0.15; // Fraction of the screen covered by the vignette on each side
+
+/// Follows the player and shakes with the trauma of hits, which fades over time.
+/// Also draws the red vignettes when the player is hurt or low on HP.
+pub(crate) struct GameCamera {
+    /// From 0 to 1, the shake grows with its square
+    trauma: f32,
+    /// Seconds since the start of the run, moving the shake
+    time: f32,
+    damage_flash_timer: f32,
+}
+
+impl GameCamera {
+    pub(crate) fn new() -> Self {
+        Self {
+            trauma: 0.,
+            time: 0.,
+            damage_flash_timer: 0.,
+        }
+    }
+
+    /// Shake the view and flash it red when the player is hit, but not by a burn or poison tick,
+    /// shake it when a big ennemy is killed
+    pub(crate) fn on_damage(&mut self, event: &DamageEvent) {
+        if event.to_player() && !event.is_tick() {
+            self.add_trauma(PLAYER_HIT_TRAUMA);
+            self.damage_flash_timer = DAMAGE_FLASH_DURATION;
+        } else if event.kill && event.target_max_hp >= BIG_KILL_MIN_HP {
+            self.add_trauma(BIG_KILL_TRAUMA);
+        }
+    }
+
+    /// Shake the view when a boss slams the ground or enters a new phase, whether the player is hit or not
+    pub(crate) fn on_boss_impact(&mut self) {
+        self.add_trauma(BOSS_IMPACT_TRAUMA);
+    }
+
+    fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.);
+    }
+
+    /// Called every frame, even when the game is frozen, so the effects fade in real time
+    pub(crate) fn update(&mut self) {
+        let dt = get_frame_time();
+        self.time += dt;
+        self.trauma = (self.trauma - TRAUMA_DECAY * dt).max(0.);
+        self.damage_flash_timer -= dt;
+    }
+
+    /// World position of the upper left corner of the screen, centered on the target.
+    /// The shake is left out with reduced motion.
+    pub(crate) fn screen_origin(&self, target: Vec2, settings: &Settings) -> Vec2 {
+        let origin = Vec2 {
+            x: target.x - screen_width() / 2.,
+            y: target.y - screen_height() / 2.,
+        };
+        if settings.reduced_motion {
+            return origin;
+        }
+        // Sums of sines at unrelated frequencies move the view smoothly without repeating
+        let t = self.time * SHAKE_FREQUENCY;
+        let shake = Vec2 {
+            x: (t * 1.1).sin() * 0.6 + (t * 2.3).sin() * 0.4,
+            y: (t * 1.7).sin() * 0.6 + (t * 2.9).cos() * 0.4,
+        };
+        origin + shake * MAX_SHAKE_OFFSET * self.trauma * self.trauma
+    }
+
+    /// Red vignette flashing when the player is hit and pulsing while its HP is low
+    pub(crate) fn draw_vignettes(&self, hp_ratio: f32) {
+        if self.damage_flash_timer > 0. {
+            let alpha = DAMAGE_FLASH_COLOR.a * self.damage_flash_timer / DAMAGE_FLASH_DURATION;
+            draw_vignette(DAMAGE_FLASH_COLOR.with_alpha(alpha));
+        }
+        if hp_ratio < LOW_HP_RATIO {
+            let pulse = 0.5 + 0.5 * (self.time * LOW_HP_PULSE_SPEED).sin();
+            draw_vignette(LOW_HP_COLOR.with_alpha(LOW_HP_COLOR.a * pulse));
+        }
+    }
+}
+
+/// Frame of color on the edges of the screen, fading to transparent towards the center
+fn draw_vignette(color: Color) {
+    let (width, height) = (screen_width(), screen_height());
+    let (inset_x, inset_y) = (width * VIGNETTE_WIDTH, height * VIGNETTE_WIDTH);
+    let outer = [(0., 0.), (width, 0.), (width, height), (0., height)];
+    let inner = [(inset_x, inset_y), (width - inset_x, inset_y), (width - inset_x, height - inset_y),
+        (inset_x, height - inset_y)];
+    let mut vertices = Vec::with_capacity(8);
+    vertices.extend(outer.iter().map(|(x, y)| Vertex::new(*x, *y, 0., 0., 0., color)));
+    vertices.extend(inner.iter().map(|(x, y)| Vertex::new(*x, *y, 0., 0., 0., color.with_alpha(0.))));
+    // Two triangles per side, between the outer corners (0 to 3) and the inner ones (4 to 7)
+    let mut indices = Vec::with_capacity(24);
+    for side in 0..4u16 {
+        let next = (side + 1) % 4;
+        indices.extend([side, next, side + 4, next, next + 4, side + 4]);
+    }
+    draw_mesh(&Mesh { vertices, indices, texture: None });
+}
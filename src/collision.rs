@@ -1,10 +1,41 @@
 use macroquad::prelude::*;
 use macroquad::math::Circle;
+use serde::Deserialize;
 
 pub trait Collidable {
     fn hitbox(&self) -> Hitbox;
+    fn layer(&self) -> CollisionLayer;
+}
+
+/// What a hitbox belongs to, deciding which other hitboxes it can touch
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CollisionLayer {
+    Player,
+    Ennemy,
+    PlayerWeapon,
+    EnnemyProjectile,
+    Prop,
+}
+
+impl CollisionLayer {
+    pub fn collides_with(self, other: CollisionLayer) -> bool {
+        use CollisionLayer::*;
+        matches!((self, other),
+            (Player, Ennemy) | (Ennemy, Player)
+            | (Player, EnnemyProjectile) | (EnnemyProjectile, Player)
+            | (PlayerWeapon, Ennemy) | (Ennemy, PlayerWeapon)
+            | (PlayerWeapon, Prop) | (Prop, PlayerWeapon)
+            // Props stop the projectiles of the ennemies, not the ennemies themselves
+            | (EnnemyProjectile, Prop) | (Prop, EnnemyProjectile))
+    }
+}
+
+/// Whether two objects touch, when their layers can
+pub fn collides(a: &impl Collidable, b: &impl Collidable) -> bool {
+    a.layer().collides_with(b.layer()) && hitbox_intersects(&a.hitbox(), &b.hitbox())
 }
 
+#[derive(Clone, Deserialize)]
 pub struct HitboxParams {
     pub size: Vec2,
     // Offset from the center of the Texture
@@ -43,6 +74,7 @@ fn draw_circle_hitbox(c: &Circle, screen_center_position: Vec2, color: Color) {
     draw_circle_lines(center.x, center.y, c.r, thickness, color);
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub enum Hitbox {
     OBB(OBB),
     Circle(Circle),
@@ -141,6 +173,7 @@ fn closest_point_on_obb(obb: &OBB, point: Vec2) -> Vec2 {
 }
 
 // Oriented Bounding Box
+#[allow(clippy::upper_case_acronyms)]
 pub struct OBB {
     pub world_center_position: Vec2,
     pub half: Vec2,
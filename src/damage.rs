@@ -0,0 +1,341 @@
+use ::rand::Rng;
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{data::ensure, weapons::weapon_slot::WeaponKind};
+
+const CRIT_CHANCE: f32 = 0.05; // Chance for a weapon hit to be critical, before luck
+const CRIT_MULTIPLIER: f32 = 2.; // Damage multiplier of critical hits, before luck
+
+/// Kind of damage, each one resisted on its own
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub(crate) enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Ice,
+    Lightning,
+    Poison,
+}
+
+/// Fraction of the damage of each type ignored, negative for weaknesses
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct Resistances {
+    pub(crate) physical: f32,
+    pub(crate) fire: f32,
+    pub(crate) ice: f32,
+    pub(crate) lightning: f32,
+    pub(crate) poison: f32,
+}
+
+impl Resistances {
+    /// Damage left once the resistance to its type is applied. Raised past 1, a resistance still can't heal.
+    pub(crate) fn apply(&self, damage: f32, damage_type: DamageType) -> f32 {
+        let resistance = match damage_type {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Ice => self.ice,
+            DamageType::Lightning => self.lightning,
+            DamageType::Poison => self.poison,
+        };
+        damage * (1. - resistance.min(1.))
+    }
+
+    /// Add `resistance` to the resistance to every type
+    pub(crate) fn raise(&mut self, resistance: f32) {
+        for value in [&mut self.physical, &mut self.fire, &mut self.ice, &mut self.lightning, &mut self.poison] {
+            *value += resistance;
+        }
+    }
+
+    /// `field` names the resistances in the errors
+    pub(crate) fn validate(&self, field: &str) -> Result<(), String> {
+        for (name, resistance) in [("physical", self.physical), ("fire", self.fire), ("ice", self.ice),
+            ("lightning", self.lightning), ("poison", self.poison)] {
+            ensure((-1. ..=1.).contains(&resistance), &format!("{field}.{name}"),
+                &format!("must be between -1 and 1, got {resistance}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// What dealt a hit
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum DamageSource {
+    Weapon(WeaponKind),
+    /// Tick of a burn or a poison
+    Status,
+    /// Bomb pickup
+    Bomb,
+    /// Touch, projectile or attack of an ennemy
+    Ennemy,
+}
+
+impl DamageSource {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            DamageSource::Weapon(kind) => kind.label(),
+            DamageSource::Status => "Burns and poisons",
+            DamageSource::Bomb => "Bombs",
+            DamageSource::Ennemy => "Ennemies",
+        }
+    }
+}
+
+/// Who took a hit
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum DamageTarget {
+    Player,
+    Ennemy,
+    Prop,
+}
+
+/// A hit before its crit is rolled and the defenses of its target are applied
+pub(crate) struct Hit {
+    pub(crate) source: DamageSource,
+    pub(crate) base_damage: f32,
+    pub(crate) damage_type: DamageType,
+    pub(crate) crit_chance: f32,
+    pub(crate) crit_multiplier: f32,
+}
+
+impl Hit {
+    /// Hit that can't be critical
+    pub(crate) fn new(source: DamageSource, base_damage: f32, damage_type: DamageType) -> Self {
+        Self { source, base_damage, damage_type, crit_chance: 0., crit_multiplier: 1. }
+    }
+
+    /// Hit of a weapon of the player, its luck making crits more likely and the luck above 1 adding to their multiplier
+    pub(crate) fn weapon(kind: WeaponKind, base_damage: f32, damage_type: DamageType, luck: f32) -> Self {
+        Self {
+            source: DamageSource::Weapon(kind),
+            base_damage,
+            damage_type,
+            crit_chance: CRIT_CHANCE * luck,
+            crit_multiplier: CRIT_MULTIPLIER + (luck - 1.).max(0.),
+        }
+    }
+}
+
+/// What the damage pipeline can hurt
+pub(crate) trait Damageable {
+    /// Lose HP through the defenses of the target, once the crit is rolled.
+    /// Returns the damage actually taken and whether the hit kills the target.
+    fn take_hit(&mut self, damage: f32, damage_type: DamageType, source: DamageSource) -> (f32, bool);
+    fn target(&self) -> DamageTarget;
+    /// Where the hit is shown
+    fn hit_position(&self) -> Vec2;
+    fn max_hp(&self) -> f32;
+}
+
+/// Resolve a hit on its target: roll the crit, apply the defenses of the target and describe the result
+pub(crate) fn deal_damage(hit: Hit, target: &mut impl Damageable, rng: &mut impl Rng) -> DamageEvent {
+    let crit = hit.crit_chance > 0. && rng.random::<f32>() < hit.crit_chance;
+    let damage = if crit { hit.base_damage * hit.crit_multiplier } else { hit.base_damage };
+    let (damage, kill) = target.take_hit(damage, hit.damage_type, hit.source);
+    DamageEvent {
+        world_position: target.hit_position(),
+        source: hit.source,
+        damage_type: hit.damage_type,
+        damage,
+        crit,
+        target: target.target(),
+        kill,
+        target_max_hp: target.max_hp(),
+    }
+}
+
+/// A hit dealt during the frame, for the feedback, the camera, the stats and the on-kill triggers to react to
+pub(crate) struct DamageEvent {
+    pub(crate) world_position: Vec2,
+    pub(crate) source: DamageSource,
+    pub(crate) damage_type: DamageType,
+    /// Damage actually taken by the target
+    pub(crate) damage: f32,
+    pub(crate) crit: bool,
+    pub(crate) target: DamageTarget,
+    /// The hit killed its target
+    pub(crate) kill: bool,
+    /// Max HP of the target, telling big ennemies apart
+    pub(crate) target_max_hp: f32,
+}
+
+impl DamageEvent {
+    pub(crate) fn to_player(&self) -> bool {
+        self.target == DamageTarget::Player
+    }
+
+    /// Damage of a burn or poison tick rather than of a hit
+    pub(crate) fn is_tick(&self) -> bool {
+        self.source == DamageSource::Status
+    }
+}
+
+/// Damage totals of a run, shown once it is over
+#[derive(Default)]
+pub(crate) struct DamageStats {
+    /// Damage dealt to the ennemies and kills of each source, in the order they first hit
+    dealt: Vec<(DamageSource, f32, u32)>,
+    taken: f32,
+    crits: u32,
+    biggest_hit: f32,
+}
+
+impl DamageStats {
+    pub(crate) fn record(&mut self, event: &DamageEvent) {
+        match event.target {
+            DamageTarget::Player => self.taken += event.damage,
+            DamageTarget::Ennemy => {
+                let index = match self.dealt.iter().position(|(source, _, _)| *source == event.source) {
+                    Some(index) => index,
+                    None => {
+                        self.dealt.push((event.source, 0., 0));
+                        self.dealt.len() - 1
+                    }
+                };
+                let (_, dealt, kills) = &mut self.dealt[index];
+                *dealt += event.damage;
+                *kills += event.kill as u32;
+                self.crits += event.crit as u32;
+                self.biggest_hit = self.biggest_hit.max(event.damage);
+            }
+            DamageTarget::Prop => (),
+        }
+    }
+
+    /// One line per source of damage, then the totals
+    pub(crate) fn report(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.dealt.iter()
+            .map(|(source, dealt, kills)| format!("{} : {dealt:.0} damage, {kills} kills", source.label()))
+            .collect();
+        lines.push(format!("Damage taken : {:.0}", self.taken));
+        lines.push(format!("Critical hits : {}, biggest hit : {:.0}", self.crits, self.biggest_hit));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::entity::{elite::EliteModifier, ennemy::Ennemy, ennemy_definition::EnnemyDefinition};
+
+    const ENNEMY: &str = r#"(
+        name: "Dummy",
+        animation: (
+            frame_width: 32.0,
+            frame_height: 32.0,
+            clips: [
+                (name: "idle", texture: "dummy.png", frames: [0], frame_duration: 1.0, looping: true),
+                (name: "walk", texture: "dummy.png", frames: [0], frame_duration: 1.0, looping: true),
+            ],
+        ),
+        hitbox: (size: (16.0, 16.0), offset_frame: (0.0, 0.0)),
+        hp: 10.0,
+        speed: 1.0,
+        damage: 1.0,
+        resistances: (fire: -0.5),
+        hurt_cooldown: 0.0,
+    )"#;
+
+    fn ennemy() -> (Ennemy, EnnemyDefinition) {
+        let definition: EnnemyDefinition = ron::from_str(ENNEMY).unwrap();
+        (Ennemy::new(Vec2::ZERO, Vec2::ZERO, 0, &definition, 1), definition)
+    }
+
+    fn event(source: DamageSource, damage: f32, crit: bool, target: DamageTarget, kill: bool) -> DamageEvent {
+        DamageEvent {
+            world_position: Vec2::ZERO,
+            source,
+            damage_type: DamageType::Physical,
+            damage,
+            crit,
+            target,
+            kill,
+            target_max_hp: 10.,
+        }
+    }
+
+    #[test]
+    fn resistances_reduce_and_weaknesses_raise_the_damage() {
+        let resistances = Resistances { physical: 0.25, fire: -0.5, ..Default::default() };
+        assert_eq!(resistances.apply(10., DamageType::Physical), 7.5);
+        assert_eq!(resistances.apply(10., DamageType::Fire), 15.);
+        assert_eq!(resistances.apply(10., DamageType::Ice), 10.);
+    }
+
+    #[test]
+    fn resistances_raised_past_1_ignore_the_whole_damage() {
+        let mut resistances = Resistances { poison: 0.8, ..Default::default() };
+        resistances.raise(0.25);
+        assert_eq!(resistances.apply(10., DamageType::Poison), 0.);
+    }
+
+    #[test]
+    fn crits_multiply_the_damage() {
+        let (mut ennemy, _) = ennemy();
+        let mut rng = StdRng::seed_from_u64(7);
+        let hit = Hit { crit_chance: 1., ..Hit::weapon(WeaponKind::Sword, 2., DamageType::Physical, 1.) };
+        let event = deal_damage(hit, &mut ennemy, &mut rng);
+        assert!(event.crit);
+        assert_eq!(event.damage, 2. * CRIT_MULTIPLIER);
+        assert!(!event.kill);
+
+        let event = deal_damage(Hit::new(DamageSource::Bomb, 2., DamageType::Physical), &mut ennemy, &mut rng);
+        assert!(!event.crit);
+        assert_eq!(event.damage, 2.);
+    }
+
+    #[test]
+    fn luck_raises_the_crit_multiplier() {
+        let hit = Hit::weapon(WeaponKind::Sword, 1., DamageType::Physical, 1.5);
+        assert_eq!(hit.crit_multiplier, CRIT_MULTIPLIER + 0.5);
+        assert_eq!(hit.crit_chance, CRIT_CHANCE * 1.5);
+    }
+
+    #[test]
+    fn killing_hit_is_reported_through_the_weakness() {
+        let (mut ennemy, _) = ennemy();
+        let mut rng = StdRng::seed_from_u64(7);
+        let event = deal_damage(Hit::new(DamageSource::Bomb, 8., DamageType::Fire), &mut ennemy, &mut rng);
+        assert_eq!(event.damage, 12.);
+        assert!(event.kill);
+        assert!(event.target == DamageTarget::Ennemy);
+        assert!(!ennemy.is_alive());
+    }
+
+    #[test]
+    fn shield_absorbs_a_hit_but_not_the_ticks() {
+        let (mut ennemy, definition) = ennemy();
+        ennemy.make_elite(vec![EliteModifier::Shielded { hits: 1 }], WHITE, &definition);
+        let mut rng = StdRng::seed_from_u64(7);
+        let event = deal_damage(Hit::new(DamageSource::Bomb, 5., DamageType::Physical), &mut ennemy, &mut rng);
+        assert_eq!(event.damage, 0.);
+        assert_eq!(ennemy.character.hp, 10.);
+
+        let event = deal_damage(Hit::new(DamageSource::Status, 1., DamageType::Poison), &mut ennemy, &mut rng);
+        assert_eq!(event.damage, 1.);
+        let event = deal_damage(Hit::new(DamageSource::Bomb, 5., DamageType::Physical), &mut ennemy, &mut rng);
+        assert_eq!(event.damage, 5.);
+        assert_eq!(ennemy.character.hp, 4.);
+    }
+
+    #[test]
+    fn stats_add_up_the_damage_of_each_source() {
+        let mut stats = DamageStats::default();
+        let sword = DamageSource::Weapon(WeaponKind::Sword);
+        stats.record(&event(sword, 4., true, DamageTarget::Ennemy, false));
+        stats.record(&event(DamageSource::Bomb, 10., false, DamageTarget::Ennemy, true));
+        stats.record(&event(sword, 6., false, DamageTarget::Ennemy, true));
+        stats.record(&event(DamageSource::Ennemy, 3., false, DamageTarget::Player, false));
+        stats.record(&event(sword, 50., true, DamageTarget::Prop, true));
+        assert_eq!(stats.report(), vec![
+            format!("{} : 10 damage, 1 kills", sword.label()),
+            "Bombs : 10 damage, 1 kills".to_string(),
+            "Damage taken : 3".to_string(),
+            "Critical hits : 1, biggest hit : 10".to_string(),
+        ]);
+    }
+}
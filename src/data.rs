@@ -0,0 +1,92 @@
+use macroquad::prelude::*;
+use serde::de::DeserializeOwned;
+
+use crate::asset_manager::AssetManager;
+use crate::entity::character_definition::{CharacterDefinition, load_roster};
+use crate::entity::elite::EliteDefinitions;
+use crate::entity::ennemy_definition::{EnnemyDefinition, load_ennemy_definitions};
+use crate::entity::prop_definition::PropDefinitions;
+use crate::game::{GRASS_TEXTURE_PATH, GameTextures};
+use crate::particles::emitter_definition::{EmitterDefinition, ParticleShape, load_emitter_definitions};
+use crate::survivor_ui::skin::UI_FONT_PATH;
+use crate::weapons::weapon_definition::WeaponDefinitions;
+
+/// Every data definition of the game
+pub(crate) struct Definitions {
+    pub(crate) roster: Vec<CharacterDefinition>,
+    pub(crate) weapons: WeaponDefinitions,
+    pub(crate) ennemies: Vec<EnnemyDefinition>,
+    pub(crate) particles: Vec<EmitterDefinition>,
+    pub(crate) props: PropDefinitions,
+    pub(crate) elites: EliteDefinitions,
+}
+
+impl Definitions {
+    pub(crate) async fn load() -> Result<Self, String> {
+        Ok(Self {
+            roster: load_roster().await?,
+            weapons: WeaponDefinitions::load().await?,
+            ennemies: load_ennemy_definitions().await?,
+            particles: load_emitter_definitions().await?,
+            props: PropDefinitions::load().await?,
+            elites: EliteDefinitions::load().await?,
+        })
+    }
+
+    /// Queue the sprites of every character, weapon, ennemy and particle
+    pub(crate) fn queue_textures(&self, assets: &mut AssetManager) {
+        for character in 0..self.roster.len() {
+            self.textures(character, assets);
+        }
+    }
+
+    /// Textures of a run with the given character. The ones not queued yet are queued.
+    pub(crate) fn textures(&self, character: usize, assets: &mut AssetManager) -> GameTextures {
+        let character = &self.roster[character];
+        GameTextures {
+            // Weapons are drawn much smaller than their png
+            sword: assets.queue_sprite(&self.weapons.sword.texture, true),
+            dagger: assets.queue_sprite(&self.weapons.daggers.texture, true),
+            player: character.animation.queue_sprites(assets),
+            ennemies: self.ennemies.iter()
+                .map(|definition| definition.animation.queue_sprites(assets))
+                .collect(),
+            // Particles are drawn much smaller than their png too
+            particles: self.particles.iter()
+                .map(|definition| match &definition.shape {
+                    ParticleShape::Sprite(path) => Some(assets.queue_sprite(path, true)),
+                    _ => None,
+                })
+                .collect(),
+            grass: assets.queue_texture(GRASS_TEXTURE_PATH),
+            font: assets.queue_font(UI_FONT_PATH),
+        }
+    }
+}
+
+/// Read and parse a RON file of the assets folder, the error naming the path and the reason when it can't
+pub(crate) async fn load_ron<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let content = match load_string(path).await {
+        Ok(content) => content,
+        Err(error) => return Err(format!("Can't read {path}: {error}")),
+    };
+    ron::from_str::<T>(&content).map_err(|error| format!("Invalid {path}: {error}"))
+}
+
+/// Error returned by the validation of a definition, naming the faulty field
+pub(crate) fn ensure(condition: bool, field: &str, requirement: &str) -> Result<(), String> {
+    if condition { Ok(()) } else { Err(format!("{field} {requirement}")) }
+}
+
+pub(crate) fn ensure_positive(value: f32, field: &str) -> Result<(), String> {
+    ensure(value > 0., field, &format!("must be greater than 0, got {value}"))
+}
+
+pub(crate) fn ensure_non_negative(value: f32, field: &str) -> Result<(), String> {
+    ensure(value >= 0., field, &format!("can't be negative, got {value}"))
+}
+
+/// Ratios of a texture size must be in ]0, 1]
+pub(crate) fn ensure_ratio(value: f32, field: &str) -> Result<(), String> {
+    ensure(value > 0. && value <= 1., field, &format!("must be between 0 (excluded) and 1, got {value}"))
+}
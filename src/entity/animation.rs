@@ -0,0 +1,237 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{asset_manager::{AssetManager, SpriteHandle}, atlas::Sprite, data::{ensure, ensure_positive}, entity::character::Direction};
+
+pub(crate) const IDLE: &str = "idle";
+pub(crate) const WALK: &str = "walk";
+pub(crate) const ATTACK: &str = "attack";
+pub(crate) const HURT: &str = "hurt";
+pub(crate) const DEATH: &str = "death";
+
+/// Clips of a character, all cut from sprite sheets with frames of the same size
+#[derive(Clone, Deserialize)]
+pub(crate) struct AnimationSet {
+    pub(crate) frame_width: f32,
+    pub(crate) frame_height: f32,
+    pub(crate) clips: Vec<Clip>,
+}
+
+/// A named animation, like "idle" or "walk". Clips other than idle and walk are optional,
+/// playing a missing one does nothing.
+#[derive(Clone, Deserialize)]
+pub(crate) struct Clip {
+    pub(crate) name: String,
+    /// Sprite sheet with the frames in columns and the directions in rows
+    pub(crate) texture: String,
+    /// Columns of the sheet, in the order they are played
+    pub(crate) frames: Vec<u8>,
+    pub(crate) frame_duration: f32,
+    /// A clip which doesn't loop stays on its last frame, then lets idle or walk play again
+    #[serde(default)]
+    pub(crate) looping: bool,
+    #[serde(default)]
+    pub(crate) directions: Directions,
+    #[serde(default)]
+    pub(crate) events: Vec<AnimationEvent>,
+}
+
+/// Row of the sheet used for each direction
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) struct Directions {
+    pub(crate) up: DirectionVariant,
+    pub(crate) down: DirectionVariant,
+    pub(crate) left: DirectionVariant,
+    pub(crate) right: DirectionVariant,
+}
+
+/// A row of the sheet, mirrored horizontally when `flip` is set so that e.g. right can reuse the left row
+#[derive(Clone, Copy, Default, Deserialize)]
+pub(crate) struct DirectionVariant {
+    pub(crate) row: u8,
+    #[serde(default)]
+    pub(crate) flip: bool,
+}
+
+/// Event sent when a clip reaches one of its frames, e.g. "throw" to throw the daggers
+#[derive(Clone, Deserialize)]
+pub(crate) struct AnimationEvent {
+    /// Index in the frames of the clip
+    pub(crate) frame: usize,
+    pub(crate) name: String,
+}
+
+/// Current clip and frame of a character
+pub(crate) struct Animator {
+    clip: usize,
+    /// Index in the frames of the clip
+    frame: usize,
+    timer: f32,
+    finished: bool,
+    /// The events of the first frame are sent on the update following `play`
+    just_started: bool,
+}
+
+impl AnimationSet {
+    pub(crate) fn clip_index(&self, name: &str) -> Option<usize> {
+        self.clips.iter().position(|clip| clip.name == name)
+    }
+
+    pub(crate) fn has_event(&self, clip: &str, event: &str) -> bool {
+        self.clip_index(clip)
+            .is_some_and(|index| self.clips[index].events.iter().any(|clip_event| clip_event.name == event))
+    }
+
+    /// Queue the sprite sheet of every clip, the handles being in the order of the clips
+    pub(crate) fn queue_sprites(&self, assets: &mut AssetManager) -> Vec<SpriteHandle> {
+        self.clips.iter()
+            .map(|clip| assets.queue_sprite(&clip.texture, false))
+            .collect()
+    }
+
+    /// Region of the current frame in the atlas, and whether it is drawn flipped
+    fn frame_region(&self, sprite: &Sprite, animator: &Animator, direction: &Direction) -> (Rect, bool) {
+        let clip = &self.clips[animator.clip];
+        let variant = clip.directions.variant(direction);
+        let sheet = sprite.region();
+        let region = Rect::new(
+            sheet.x + clip.frames[animator.frame] as f32 * self.frame_width,
+            sheet.y + variant.row as f32 * self.frame_height,
+            self.frame_width,
+            self.frame_height,
+        );
+        (region, variant.flip)
+    }
+
+    pub(crate) fn validate(&self, name: &str) -> Result<(), String> {
+        ensure_positive(self.frame_width, &format!("{name}.animation.frame_width"))?;
+        ensure_positive(self.frame_height, &format!("{name}.animation.frame_height"))?;
+        for required in [IDLE, WALK] {
+            ensure(self.clip_index(required).is_some(), &format!("{name}.animation.clips"),
+                &format!("must have a \"{required}\" clip"))?;
+        }
+        for clip in &self.clips {
+            let field = format!("{name}.animation.{}", clip.name);
+            ensure(!clip.frames.is_empty(), &format!("{field}.frames"), "can't be empty")?;
+            ensure_positive(clip.frame_duration, &format!("{field}.frame_duration"))?;
+            for event in &clip.events {
+                ensure(event.frame < clip.frames.len(), &format!("{field}.events.{}", event.name),
+                    &format!("must be on one of the {} frames, got {}", clip.frames.len(), event.frame))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Clip {
+    fn events_at(&self, frame: usize) -> impl Iterator<Item = String> + '_ {
+        self.events.iter()
+            .filter(move |event| event.frame == frame)
+            .map(|event| event.name.clone())
+    }
+}
+
+impl Directions {
+    fn variant(&self, direction: &Direction) -> DirectionVariant {
+        match direction {
+            Direction::None | Direction::Up => self.up,
+            Direction::Down => self.down,
+            Direction::Left => self.left,
+            Direction::Right => self.right,
+        }
+    }
+}
+
+impl Default for Directions {
+    /// Layout of the sheets of the game: up, left, down then right
+    fn default() -> Self {
+        Self {
+            up: DirectionVariant { row: 0, flip: false },
+            left: DirectionVariant { row: 1, flip: false },
+            down: DirectionVariant { row: 2, flip: false },
+            right: DirectionVariant { row: 3, flip: false },
+        }
+    }
+}
+
+impl Animator {
+    /// Start on the idle clip, which every validated set has
+    pub(crate) fn new(set: &AnimationSet) -> Self {
+        Self {
+            clip: set.clip_index(IDLE).unwrap_or(0),
+            frame: 0,
+            timer: 0.,
+            finished: false,
+            just_started: true,
+        }
+    }
+
+    pub(crate) fn clip(&self) -> usize {
+        self.clip
+    }
+
+    /// Play a clip from its first frame, unless it is already playing. Returns false when the set doesn't have it.
+    pub(crate) fn play(&mut self, set: &AnimationSet, name: &str) -> bool {
+        let Some(clip) = set.clip_index(name) else {
+            return false;
+        };
+        if clip != self.clip || self.finished {
+            *self = Self { clip, frame: 0, timer: 0., finished: false, just_started: true };
+        }
+        true
+    }
+
+    /// Switch between looping clips like idle and walk, without cutting a clip which doesn't loop
+    pub(crate) fn play_loop(&mut self, set: &AnimationSet, name: &str) {
+        if set.clips[self.clip].looping || self.finished {
+            self.play(set, name);
+        }
+    }
+
+    pub(crate) fn is_playing(&self, set: &AnimationSet, name: &str) -> bool {
+        set.clips[self.clip].name == name && !self.finished
+    }
+
+    /// Advance the clip, returning the events of the frames reached
+    pub(crate) fn update(&mut self, set: &AnimationSet, dt: f32) -> Vec<String> {
+        let clip = &set.clips[self.clip];
+        let mut events = Vec::new();
+        if self.just_started {
+            self.just_started = false;
+            events.extend(clip.events_at(0));
+        }
+        self.timer += dt;
+        while !self.finished && self.timer >= clip.frame_duration {
+            self.timer -= clip.frame_duration;
+            if self.frame + 1 < clip.frames.len() {
+                self.frame += 1;
+            } else if clip.looping {
+                self.frame = 0;
+            } else {
+                self.finished = true;
+                break;
+            }
+            events.extend(clip.events_at(self.frame));
+        }
+        events
+    }
+
+    /// Draw the current frame centered on the position, `scale` times its size
+    pub(crate) fn draw(&self, set: &AnimationSet, sprite: &Sprite, direction: &Direction, screen_position: Vec2,
+        color: Color, scale: f32) {
+        let (source, flip_x) = set.frame_region(sprite, self, direction);
+        let size = Vec2 { x: set.frame_width * scale, y: set.frame_height * scale };
+        draw_texture_ex(
+            sprite.texture,
+            screen_position.x - size.x / 2.0,
+            screen_position.y - size.y / 2.0,
+            color,
+            DrawTextureParams {
+                source: Some(source),
+                dest_size: Some(size),
+                flip_x,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+
+use crate::{entity::boss_definition::{BossAttack, BossDefinition, BossPhase}, telegraph::{Telegraph, TelegraphShape}};
+
+const CONTACT_COOLDOWN: f32 = 1.; // Seconds before a boss touching the player can hurt it again
+const CHARGE_WIDTH: f32 = 30.; // Width of the lane warning of a charge
+const RING_MARKER_DISTANCE: f32 = 40.; // Distance from the boss of the markers of the projectiles about to be fired
+const RING_MARKER_RADIUS: f32 = 4.;
+const SUMMON_RADIUS: f32 = 50.; // Distance from the boss at which the minions appear
+const TELEGRAPH_COLOR: Color = Color::new(1., 0.1, 0.1, 0.35);
+const SUMMON_COLOR: Color = Color::new(0.6, 0.2, 0.9, 0.35);
+
+/// Attack landing at the end of its telegraph, for the game to resolve
+pub(crate) enum BossAction {
+    /// Hit the player if it stands in the shape
+    Strike { origin: Vec2, shape: TelegraphShape, damage: f32 },
+    /// Fire bolts towards the directions, `speed` being the distance they travel each frame
+    Ring { center: Vec2, directions: Vec<Vec2>, speed: f32, damage: f32 },
+    Summon { ennemy: String, positions: Vec<Vec2> },
+    /// The boss entered its next phase
+    NewPhase { center: Vec2 },
+}
+
+/// Attack patterns of a boss, going through the phases of its definition as it loses HP
+pub(crate) struct BossBrain {
+    phase: usize,
+    /// Index of the next attack of the phase
+    attack: usize,
+    state: BossState,
+    /// Rings fired so far, turning the next one
+    rings: u32,
+    contact_timer: f32,
+}
+
+enum BossState {
+    /// Walking towards the player until the next attack
+    Walking { timer: f32 },
+    /// Standing still, warning of the attack about to land
+    Telegraph(Telegraph),
+    Charging { direction: Vec2, distance_left: f32 },
+}
+
+impl BossBrain {
+    pub(crate) fn new(definition: &BossDefinition) -> Self {
+        Self {
+            phase: 0,
+            attack: 0,
+            state: BossState::Walking { timer: definition.phases[0].cooldown },
+            rings: 0,
+            contact_timer: 0.,
+        }
+    }
+
+    fn current_phase<'a>(&self, definition: &'a BossDefinition) -> &'a BossPhase {
+        // A reloaded definition can have fewer phases
+        &definition.phases[self.phase.min(definition.phases.len() - 1)]
+    }
+
+    fn current_attack<'a>(&self, definition: &'a BossDefinition) -> &'a BossAttack {
+        let attacks = &self.current_phase(definition).attacks;
+        &attacks[self.attack % attacks.len()]
+    }
+
+    /// Advance the pattern, returning the movement of the boss for the frame and the actions landing on it.
+    /// `walk` is the movement of the boss when it walks towards the player like the other ennemies.
+    pub(crate) fn update(&mut self, definition: &BossDefinition, position: Vec2, player_position: Vec2,
+        hp_ratio: f32, walk: Vec2) -> (Vec2, Vec<BossAction>) {
+        let dt = get_frame_time();
+        self.contact_timer -= dt;
+        let mut actions = Vec::new();
+        let next_phase = definition.phases.iter()
+            .rposition(|phase| hp_ratio <= phase.hp_threshold)
+            .unwrap_or(0);
+        if next_phase > self.phase {
+            // A new phase interrupts the current attack and starts with its first one
+            self.phase = next_phase;
+            self.attack = 0;
+            self.state = BossState::Walking { timer: self.current_phase(definition).cooldown };
+            actions.push(BossAction::NewPhase { center: position });
+        }
+
+        let phase = self.current_phase(definition);
+        let attack = self.current_attack(definition);
+        let movement = match &mut self.state {
+            BossState::Walking { timer } => {
+                *timer -= dt;
+                if *timer <= 0. {
+                    let shape = telegraph_shape(attack, position, player_position);
+                    self.state = BossState::Telegraph(Telegraph::new(position, shape, attack.telegraph()));
+                }
+                walk * phase.speed
+            }
+            BossState::Telegraph(telegraph) => {
+                if telegraph.update() {
+                    // Charges go along the lane they warned of
+                    if let TelegraphShape::Line { direction, length, .. } = telegraph.shape {
+                        self.state = BossState::Charging { direction, distance_left: length };
+                    } else {
+                        let shape = telegraph.shape;
+                        actions.extend(self.action(attack, position, shape));
+                        self.end_attack(phase);
+                    }
+                }
+                Vec2::ZERO
+            }
+            BossState::Charging { direction, distance_left } => {
+                let BossAttack::Charge { speed, .. } = attack else {
+                    // The attack was changed by a reloaded definition
+                    self.end_attack(phase);
+                    return (Vec2::ZERO, actions);
+                };
+                let step = speed.min(*distance_left);
+                *distance_left -= step;
+                let movement = *direction * step;
+                if *distance_left <= 0. {
+                    self.end_attack(phase);
+                }
+                movement
+            }
+        };
+        (movement, actions)
+    }
+
+    /// Action of an attack landing at the end of its telegraph `shape`, charges moving the boss instead
+    fn action(&mut self, attack: &BossAttack, position: Vec2, shape: TelegraphShape) -> Option<BossAction> {
+        match attack {
+            BossAttack::Charge { .. } => None,
+            BossAttack::Slam { damage, .. } | BossAttack::Cleave { damage, .. } => {
+                Some(BossAction::Strike { origin: position, shape, damage: *damage })
+            }
+            BossAttack::Ring { count, speed, damage, .. } => {
+                let directions = ring_directions(*count, self.rings).collect();
+                self.rings += 1;
+                Some(BossAction::Ring { center: position, directions, speed: *speed, damage: *damage })
+            }
+            BossAttack::Summon { ennemy, count, .. } => {
+                let positions = ring_directions(*count, 0)
+                    .map(|direction| position + direction * SUMMON_RADIUS)
+                    .collect();
+                Some(BossAction::Summon { ennemy: ennemy.clone(), positions })
+            }
+        }
+    }
+
+    fn end_attack(&mut self, phase: &BossPhase) {
+        self.attack += 1;
+        self.state = BossState::Walking { timer: phase.cooldown };
+    }
+
+    pub(crate) fn is_charging(&self) -> bool {
+        matches!(self.state, BossState::Charging { .. })
+    }
+
+    /// Damage dealt to the player touched by the boss, which can't hurt it again for a moment.
+    /// A charging boss deals the damage of its charge.
+    pub(crate) fn touch(&mut self, definition: &BossDefinition, contact_damage: f32) -> Option<f32> {
+        if self.contact_timer > 0. {
+            return None;
+        }
+        self.contact_timer = CONTACT_COOLDOWN;
+        match self.current_attack(definition) {
+            BossAttack::Charge { damage, .. } if self.is_charging() => Some(*damage),
+            _ => Some(contact_damage),
+        }
+    }
+
+    /// Warning on the ground of the attack about to land
+    pub(crate) fn draw_telegraph(&self, definition: &BossDefinition, screen_origin_position: Vec2) {
+        let BossState::Telegraph(telegraph) = &self.state else {
+            return;
+        };
+        match self.current_attack(definition) {
+            BossAttack::Summon { .. } => telegraph.draw(screen_origin_position, SUMMON_COLOR),
+            BossAttack::Ring { count, .. } => {
+                // Markers where the bolts are about to be fired
+                let center = telegraph.world_position - screen_origin_position;
+                let distance = RING_MARKER_DISTANCE * (0.5 + 0.5 * telegraph.progress());
+                for direction in ring_directions(*count, self.rings) {
+                    TelegraphShape::Circle { radius: RING_MARKER_RADIUS }
+                        .draw(center + direction * distance, 1., TELEGRAPH_COLOR);
+                }
+            }
+            _ => telegraph.draw(screen_origin_position, TELEGRAPH_COLOR),
+        }
+    }
+}
+
+/// Area warned of by an attack of a boss standing at `position`, aiming at the player
+fn telegraph_shape(attack: &BossAttack, position: Vec2, player_position: Vec2) -> TelegraphShape {
+    let direction = (player_position - position).normalize_or(Vec2 { x: 0., y: 1. });
+    match attack {
+        BossAttack::Charge { distance, .. } => TelegraphShape::Line { direction, length: *distance, width: CHARGE_WIDTH },
+        BossAttack::Slam { radius, .. } => TelegraphShape::Circle { radius: *radius },
+        BossAttack::Cleave { half_angle, radius, .. } => {
+            TelegraphShape::Cone { direction, half_angle: *half_angle, radius: *radius }
+        }
+        BossAttack::Ring { .. } => TelegraphShape::Circle { radius: RING_MARKER_DISTANCE },
+        BossAttack::Summon { .. } => TelegraphShape::Circle { radius: SUMMON_RADIUS },
+    }
+}
+
+/// `count` directions evenly spread on a circle, turned by half a step on odd turns
+fn ring_directions(count: u8, turn: u32) -> impl Iterator<Item = Vec2> {
+    let step = TAU / count as f32;
+    let offset = if turn % 2 == 1 { step / 2. } else { 0. };
+    (0..count).map(move |i| Vec2::from_angle(offset + i as f32 * step))
+}
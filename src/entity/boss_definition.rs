@@ -0,0 +1,128 @@
+use std::f32::consts::PI;
+
+use serde::Deserialize;
+
+use crate::data::{ensure, ensure_non_negative, ensure_positive};
+
+/// Boss part of an ennemy definition: when it spawns and how it fights
+#[derive(Clone, Deserialize)]
+pub(crate) struct BossDefinition {
+    /// Seconds survived before the boss spawns
+    pub(crate) spawn_time: f32,
+    /// Seconds between two spawns after the first one, the boss spawning once when 0
+    #[serde(default)]
+    pub(crate) respawn_interval: f32,
+    /// Size of the drawn sprite relative to its frames, the hitbox being given at the drawn size
+    #[serde(default = "default_scale")]
+    pub(crate) scale: f32,
+    pub(crate) phases: Vec<BossPhase>,
+}
+
+/// Attacks used by a boss while its HP is in a range
+#[derive(Clone, Deserialize)]
+pub(crate) struct BossPhase {
+    /// Fraction of the max HP under which the phase starts, the first phase starting at 1
+    pub(crate) hp_threshold: f32,
+    /// Multiplier of the speed of the boss while it walks
+    #[serde(default = "default_speed")]
+    pub(crate) speed: f32,
+    /// Seconds walking towards the player between two attacks
+    pub(crate) cooldown: f32,
+    /// Used one after the other, starting over after the last one
+    pub(crate) attacks: Vec<BossAttack>,
+}
+
+/// Every attack is telegraphed for `telegraph` seconds, the boss standing still, before it lands
+#[derive(Clone, Deserialize)]
+pub(crate) enum BossAttack {
+    /// Rush in a straight line towards where the player stood when the telegraph started.
+    /// `speed` is a fraction of the player base speed, like the speed of the ennemies.
+    Charge { telegraph: f32, speed: f32, distance: f32, damage: f32 },
+    /// Hit the player if it is within `radius` of the boss
+    Slam { telegraph: f32, radius: f32, damage: f32 },
+    /// Hit the player if it is in the cone towards where it stood when the telegraph started,
+    /// spreading `half_angle` radians on each side
+    Cleave { telegraph: f32, half_angle: f32, radius: f32, damage: f32 },
+    /// Fire `count` projectiles evenly spread around the boss, each ring turned by half a step from the last one
+    Ring { telegraph: f32, count: u8, speed: f32, damage: f32 },
+    /// Spawn `count` ennemies of the named kind around the boss
+    Summon { telegraph: f32, ennemy: String, count: u8 },
+}
+
+impl BossAttack {
+    /// Seconds of warning before the attack lands
+    pub(crate) fn telegraph(&self) -> f32 {
+        match self {
+            BossAttack::Charge { telegraph, .. } | BossAttack::Slam { telegraph, .. }
+                | BossAttack::Cleave { telegraph, .. } | BossAttack::Ring { telegraph, .. }
+                | BossAttack::Summon { telegraph, .. } => *telegraph,
+        }
+    }
+}
+
+impl BossDefinition {
+    /// `name` is the one of the ennemy, `ennemies` the names of the kinds of ennemy bosses can summon
+    pub(crate) fn validate(&self, name: &str, ennemies: &[&str]) -> Result<(), String> {
+        ensure_non_negative(self.spawn_time, &format!("{name}.boss.spawn_time"))?;
+        ensure_non_negative(self.respawn_interval, &format!("{name}.boss.respawn_interval"))?;
+        ensure_positive(self.scale, &format!("{name}.boss.scale"))?;
+        ensure(!self.phases.is_empty(), &format!("{name}.boss.phases"), "needs at least one phase")?;
+        ensure(self.phases[0].hp_threshold == 1., &format!("{name}.boss.phases[0].hp_threshold"),
+            "must be 1, the first phase starting with the boss")?;
+        for (i, phase) in self.phases.iter().enumerate() {
+            let field = format!("{name}.boss.phases[{i}]");
+            ensure(phase.hp_threshold > 0. && phase.hp_threshold <= 1., &format!("{field}.hp_threshold"),
+                &format!("must be between 0 and 1, got {}", phase.hp_threshold))?;
+            if i > 0 {
+                ensure(phase.hp_threshold < self.phases[i - 1].hp_threshold, &format!("{field}.hp_threshold"),
+                    "must be lower than the one of the previous phase")?;
+            }
+            ensure_non_negative(phase.speed, &format!("{field}.speed"))?;
+            ensure_non_negative(phase.cooldown, &format!("{field}.cooldown"))?;
+            ensure(!phase.attacks.is_empty(), &format!("{field}.attacks"), "needs at least one attack")?;
+            for (j, attack) in phase.attacks.iter().enumerate() {
+                validate_attack(attack, &format!("{field}.attacks[{j}]"), ennemies)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_attack(attack: &BossAttack, field: &str, ennemies: &[&str]) -> Result<(), String> {
+    ensure_non_negative(attack.telegraph(), &format!("{field}.telegraph"))?;
+    match attack {
+        BossAttack::Charge { speed, distance, damage, .. } => {
+            ensure_positive(*speed, &format!("{field}.speed"))?;
+            ensure_positive(*distance, &format!("{field}.distance"))?;
+            ensure_non_negative(*damage, &format!("{field}.damage"))
+        }
+        BossAttack::Slam { radius, damage, .. } => {
+            ensure_positive(*radius, &format!("{field}.radius"))?;
+            ensure_non_negative(*damage, &format!("{field}.damage"))
+        }
+        BossAttack::Cleave { half_angle, radius, damage, .. } => {
+            ensure(*half_angle > 0. && *half_angle <= PI, &format!("{field}.half_angle"),
+                &format!("must be between 0 and pi, got {half_angle}"))?;
+            ensure_positive(*radius, &format!("{field}.radius"))?;
+            ensure_non_negative(*damage, &format!("{field}.damage"))
+        }
+        BossAttack::Ring { count, speed, damage, .. } => {
+            ensure(*count > 0, &format!("{field}.count"), "must fire at least one projectile")?;
+            ensure_positive(*speed, &format!("{field}.speed"))?;
+            ensure_non_negative(*damage, &format!("{field}.damage"))
+        }
+        BossAttack::Summon { ennemy, count, .. } => {
+            ensure(*count > 0, &format!("{field}.count"), "must summon at least one ennemy")?;
+            ensure(ennemies.contains(&ennemy.as_str()), &format!("{field}.ennemy"),
+                &format!("is not an ennemy that isn't a boss, got {ennemy}"))
+        }
+    }
+}
+
+fn default_scale() -> f32 {
+    1.
+}
+
+fn default_speed() -> f32 {
+    1.
+}
@@ -1,32 +1,94 @@
 use macroquad::prelude::*;
 
-use crate::{collision::{Collidable, Hitbox, HitboxParams, OBB}, draw_utils::is_on_screen};
+use crate::{atlas::Sprite, collision::{Hitbox, HitboxParams, OBB}, draw_utils::is_on_screen, entity::animation::{AnimationSet, Animator, IDLE, WALK}};
+
+const HIT_FLASH_DURATION: f32 = 0.1; // Seconds during which a struck character is drawn in white
+const FEET_HEIGHT: f32 = 6.; // Height of the bottom of the hitbox stopped by the obstacles
+const OUTLINE_WIDTH: f32 = 2.; // Pixels the outline of the elites sticks out of their sprite
 
 pub(crate) struct Character {
     pub(crate) world_position: Vec2,
-    pub(crate) hp: i16,
+    pub(crate) hp: f32,
     direction: Direction,
-    hitbox_params: HitboxParams,
-    anim_timer: f32,
-    frame: u8,
-    is_idle: bool,
+    pub(crate) hitbox_params: HitboxParams,
+    animation: AnimationSet,
+    animator: Animator,
+    flash_timer: f32,
+    /// Color multiplied with the sprite
+    pub(crate) tint: Color,
+    /// Size of the drawn sprite relative to its frames
+    pub(crate) scale: f32,
 }
 
 impl Character {
-    pub(crate) fn new(pos: Vec2, hitbox_params: HitboxParams) -> Self {
+    pub(crate) fn new(pos: Vec2, hitbox_params: HitboxParams, animation: AnimationSet) -> Self {
         Character {
             world_position: pos,
-            hp: 1,
+            hp: 1.,
             direction: Direction::Down,
             hitbox_params,
-            anim_timer: 0.0,
-            frame: 0,
-            is_idle: true,
+            animator: Animator::new(&animation),
+            animation,
+            flash_timer: 0.,
+            tint: WHITE,
+            scale: 1.,
         }
     }
     
-    pub(crate) fn draw(&mut self, idle_texture: &Texture2D, walking_texture: &Texture2D, 
-        params: &CharTextureParams, screen_origin_position: Vec2) {
+    /// Use a reloaded animation set, starting over from the idle clip
+    pub(crate) fn set_animation(&mut self, animation: AnimationSet) {
+        self.animator = Animator::new(&animation);
+        self.animation = animation;
+    }
+    
+    /// Vertical position of the feet, the bottom of the hitbox, ordering the characters drawn
+    pub(crate) fn foot_y(&self) -> f32 {
+        self.world_position.y + self.hitbox_params.offset_frame.y + self.hitbox_params.size.y / 2.
+    }
+    
+    /// Bottom of the hitbox, where the character stands, stopped by the obstacles
+    pub(crate) fn feet(&self) -> Rect {
+        let hitbox = &self.hitbox_params;
+        Rect::new(self.world_position.x + hitbox.offset_frame.x - hitbox.size.x / 2., self.foot_y() - FEET_HEIGHT,
+            hitbox.size.x, FEET_HEIGHT)
+    }
+    
+    /// Vertical position of the top of the head, the top of the hitbox
+    pub(crate) fn head_y(&self) -> f32 {
+        self.world_position.y + self.hitbox_params.offset_frame.y - self.hitbox_params.size.y / 2.
+    }
+    
+    /// Index of the current clip, which is also the index of its sprite sheet
+    pub(crate) fn clip(&self) -> usize {
+        self.animator.clip()
+    }
+    
+    pub(crate) fn animation(&self) -> &AnimationSet {
+        &self.animation
+    }
+    
+    /// Play a clip of the animation set, returning false when the set doesn't have it
+    pub(crate) fn play(&mut self, clip: &str) -> bool {
+        self.animator.play(&self.animation, clip)
+    }
+    
+    pub(crate) fn is_playing(&self, clip: &str) -> bool {
+        self.animator.is_playing(&self.animation, clip)
+    }
+    
+    /// Advance the animation and the hit flash, returning the events of the frames reached
+    pub(crate) fn update_animation(&mut self) -> Vec<String> {
+        self.flash_timer -= get_frame_time();
+        self.animator.update(&self.animation, get_frame_time())
+    }
+    
+    /// Draw the character in white for a moment, to show it was struck
+    pub(crate) fn flash(&mut self) {
+        self.flash_timer = HIT_FLASH_DURATION;
+    }
+    
+    /// `flash_material` draws the character in white after a hit, it isn't flashing when `None`
+    pub(crate) fn draw(&self, sprite: &Sprite, screen_origin_position: Vec2, flash_material: Option<&Material>) {
         let screen_position = Vec2 {
             x: self.world_position.x - screen_origin_position.x,
             y: self.world_position.y - screen_origin_position.y,
@@ -37,40 +99,16 @@ impl Character {
             return;
         }
 
-        let texture = if self.is_idle { idle_texture } else { walking_texture };
-        
-        let row = match self.direction {
-            Direction::None => 0,
-            Direction::Up => 0,
-            Direction::Left => 1,
-            Direction::Down => 2,
-            Direction::Right => 3,
-        };
-        
-        let source: Rect = Rect::new(
-            self.frame as f32 * params.frame_width,
-            row as f32 * params.frame_height,
-            params.frame_width,
-            params.frame_height,
-        );
-        
-        if self.anim_timer >= params.frame_duration {
-            self.anim_timer = 0.0;
-            self.frame = (self.frame + 1) % params.nb_frames;
+        match flash_material {
+            Some(material) if self.flash_timer > 0. => {
+                gl_use_material(material);
+                self.animator.draw(&self.animation, sprite, &self.direction, screen_position,
+                    WHITE.with_alpha(self.tint.a), self.scale);
+                gl_use_default_material();
+            }
+            _ => self.animator.draw(&self.animation, sprite, &self.direction, screen_position, self.tint, self.scale),
         }
         
-        draw_texture_ex(
-            texture,
-            screen_position.x - params.frame_width / 2.0,
-            screen_position.y - params.frame_height / 2.0,
-            WHITE,
-            DrawTextureParams {
-                source: Some(source),
-                dest_size: Some(Vec2::new(params.frame_width, params.frame_height)),
-                ..Default::default()
-            },
-        );
-        
         // Debug: draw the sword hitbox in debug builds
         #[cfg(debug_assertions)]
         {
@@ -80,25 +118,54 @@ impl Character {
         }
     }
     
+    /// Draw the shape of the current frame in a plain color around where the character is drawn.
+    /// `silhouette_material` draws the sprites in the color they are drawn with.
+    pub(crate) fn draw_outline(&self, sprite: &Sprite, screen_origin_position: Vec2, silhouette_material: &Material,
+        color: Color) {
+        let screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        gl_use_material(silhouette_material);
+        for offset in [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y] {
+            self.animator.draw(&self.animation, sprite, &self.direction, screen_position + offset * OUTLINE_WIDTH,
+                color, self.scale);
+        }
+        gl_use_default_material();
+    }
+    
+    /// Draw a tinted copy of the current frame at another position, e.g. for afterimages
+    pub(crate) fn draw_ghost(&self, sprite: &Sprite, screen_origin_position: Vec2, world_position: Vec2, color: Color) {
+        let screen_position = world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        self.animator.draw(&self.animation, sprite, &self.direction, screen_position, color, self.scale);
+    }
+    
+    /// Draw the current frame faded out and scaled down, without the hitbox, e.g. while dying
+    pub(crate) fn draw_faded(&self, sprite: &Sprite, screen_origin_position: Vec2, alpha: f32, scale: f32) {
+        let screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        let color = self.tint.with_alpha(self.tint.a * alpha);
+        self.animator.draw(&self.animation, sprite, &self.direction, screen_position, color, self.scale * scale);
+    }
+    
     pub(crate) fn move_by(&mut self, movement: Vec2, direction: Direction) {
         match direction {
-            Direction::None => {
-                self.anim_timer = 0.0;
-                self.frame = 0;
-                self.is_idle = true;
-            }
+            Direction::None => self.animator.play_loop(&self.animation, IDLE),
             _ => {
-                self.is_idle = false;
-                self.anim_timer += get_frame_time();
+                self.animator.play_loop(&self.animation, WALK);
                 self.world_position += movement;
                 self.direction = direction;
             }
         }
     }
-}
-
-impl Collidable for Character {
-    fn hitbox(&self) -> Hitbox {
+    
+    /// The player and the ennemies collide through this hitbox, on their own layer
+    pub(crate) fn hitbox(&self) -> Hitbox {
         Hitbox::OBB(OBB {
             world_center_position: Vec2 { 
                 x: self.world_position.x + self.hitbox_params.offset_frame.x,
@@ -120,10 +187,3 @@ pub enum Direction {
     Left,
     Right,
 }
-
-pub struct CharTextureParams {
-    pub(crate) frame_width: f32,
-    pub(crate) frame_height: f32,
-    pub(crate) nb_frames: u8,
-    pub(crate) frame_duration: f32,
-}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{collision::HitboxParams, data::load_ron, entity::{animation::AnimationSet, stats::{StatBonus, Stats}}, weapons::weapon_slot::WeaponKind};
+
+pub(crate) const ROSTER_PATH: &str = "assets/characters.ron";
+
+/// A playable character, as described in `assets/characters.ron`
+#[derive(Deserialize)]
+pub(crate) struct CharacterDefinition {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) animation: AnimationSet,
+    pub(crate) hitbox: HitboxParams,
+    /// Color multiplied with the sprite sheets, to tell apart characters sharing them
+    #[serde(default = "default_tint")]
+    pub(crate) tint: [f32; 4],
+    pub(crate) base_stats: Stats,
+    pub(crate) starting_weapon: WeaponKind,
+    pub(crate) passive: CharacterPassive,
+}
+
+/// Unique bonus of a character, gained again at each level
+#[derive(Deserialize)]
+pub(crate) struct CharacterPassive {
+    pub(crate) description: String,
+    pub(crate) level_bonus: StatBonus,
+}
+
+impl CharacterDefinition {
+    pub(crate) fn tint(&self) -> Color {
+        Color::new(self.tint[0], self.tint[1], self.tint[2], self.tint[3])
+    }
+}
+
+fn default_tint() -> [f32; 4] {
+    [1., 1., 1., 1.]
+}
+
+/// Load every playable character
+pub(crate) async fn load_roster() -> Result<Vec<CharacterDefinition>, String> {
+    let roster: Vec<CharacterDefinition> = load_ron(ROSTER_PATH).await?;
+    if roster.is_empty() {
+        return Err(format!("{ROSTER_PATH} doesn't define any character"));
+    }
+    for definition in &roster {
+        definition.animation.validate(&definition.name)
+            .and_then(|_| definition.base_stats.resistances.validate(&format!("{}.base_stats.resistances", definition.name)))
+            .map_err(|error| format!("Invalid {ROSTER_PATH}: {error}"))?;
+    }
+    Ok(roster)
+}
@@ -0,0 +1,114 @@
+use macroquad::prelude::*;
+
+use crate::draw_utils::is_on_screen;
+
+const CHEST_WIDTH: f32 = 22.;
+const CHEST_HEIGHT: f32 = 14.;
+const LID_HEIGHT: f32 = 6.;
+const LID_LIFT: f32 = 8.; // Height the lid rises to once open
+const OPEN_DISTANCE: f32 = 20.; // Distance from the player at which a chest opens
+const LID_OPEN_DURATION: f32 = 0.3; // Seconds for the lid to open
+const REWARD_INTERVAL: f32 = 0.3; // Seconds between two rewards popping out
+const REWARD_DURATION: f32 = 2.; // Seconds a reward stays above the chest
+const REWARD_RISE: f32 = 30.; // Pixels a reward rises while it is shown
+const REWARD_SPACING: f32 = 18.; // Vertical space between two rewards
+const REWARD_FONT_SIZE: f32 = 18.;
+const GLOW_RADIUS: f32 = 40.;
+const BOX_COLOR: Color = Color::new(0.45, 0.25, 0.1, 1.);
+const TRIM_COLOR: Color = Color::new(1., 0.8, 0.1, 1.);
+const GLOW_COLOR: Color = Color::new(1., 0.9, 0.4, 0.4);
+
+/// Treasure dropped by big ennemies, giving upgrades when the player walks on it
+pub(crate) struct Chest {
+    pub(crate) world_position: Vec2,
+    state: ChestState,
+}
+
+enum ChestState {
+    Closed,
+    /// The lid opens then the rewards pop out one after the other
+    Opening { timer: f32, rewards: Vec<String> },
+    /// Ready to be removed
+    Opened,
+}
+
+impl Chest {
+    pub(crate) fn new(world_position: Vec2) -> Self {
+        Self { world_position, state: ChestState::Closed }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        matches!(self.state, ChestState::Closed)
+    }
+
+    pub(crate) fn is_opened(&self) -> bool {
+        matches!(self.state, ChestState::Opened)
+    }
+
+    pub(crate) fn touches(&self, player_position: Vec2) -> bool {
+        self.is_closed() && self.world_position.distance(player_position) < OPEN_DISTANCE
+    }
+
+    /// Start the opening animation, showing the labels of the rewards given by the chest
+    pub(crate) fn open(&mut self, rewards: Vec<String>) {
+        self.state = ChestState::Opening { timer: 0., rewards };
+    }
+
+    pub(crate) fn update(&mut self) {
+        if let ChestState::Opening { timer, rewards } = &mut self.state {
+            *timer += get_frame_time();
+            let last_reward_end = LID_OPEN_DURATION + rewards.len() as f32 * REWARD_INTERVAL + REWARD_DURATION;
+            if *timer > last_reward_end {
+                self.state = ChestState::Opened;
+            }
+        }
+    }
+
+    /// Vertical position of the bottom of the chest, ordering it with the characters
+    pub(crate) fn foot_y(&self) -> f32 {
+        self.world_position.y + CHEST_HEIGHT / 2.
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2) {
+        let screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        let open_ratio = match &self.state {
+            ChestState::Opening { timer, .. } => (timer / LID_OPEN_DURATION).min(1.),
+            _ => 0.,
+        };
+        let (x, y) = (screen_position.x - CHEST_WIDTH / 2., screen_position.y - CHEST_HEIGHT / 2.);
+        if open_ratio > 0. {
+            draw_circle(screen_position.x, y, GLOW_RADIUS * open_ratio, GLOW_COLOR);
+        }
+        draw_rectangle(x, y, CHEST_WIDTH, CHEST_HEIGHT, BOX_COLOR);
+        draw_rectangle_lines(x, y, CHEST_WIDTH, CHEST_HEIGHT, 2., TRIM_COLOR);
+        // The lid rises and gets thinner as it tilts back
+        let lid_height = LID_HEIGHT * (1. - 0.5 * open_ratio);
+        let lid_y = y - lid_height - LID_LIFT * open_ratio;
+        draw_rectangle(x - 1., lid_y, CHEST_WIDTH + 2., lid_height, BOX_COLOR);
+        draw_rectangle_lines(x - 1., lid_y, CHEST_WIDTH + 2., lid_height, 2., TRIM_COLOR);
+        draw_rectangle(screen_position.x - 2., y - 1., 4., 4., TRIM_COLOR);
+    }
+
+    /// Labels of the rewards rising above the open chest
+    pub(crate) fn draw_rewards(&self, screen_origin_position: Vec2) {
+        let ChestState::Opening { timer, rewards } = &self.state else {
+            return;
+        };
+        let screen_position = self.world_position - screen_origin_position;
+        for (i, reward) in rewards.iter().enumerate() {
+            let age = timer - LID_OPEN_DURATION - i as f32 * REWARD_INTERVAL;
+            if !(0. ..REWARD_DURATION).contains(&age) {
+                continue;
+            }
+            let progress = age / REWARD_DURATION;
+            // Later rewards appear higher, so they don't cover the previous ones
+            let y = screen_position.y - CHEST_HEIGHT - REWARD_RISE * progress - i as f32 * REWARD_SPACING;
+            let width = measure_text(reward, None, REWARD_FONT_SIZE as u16, 1.).width;
+            draw_text(reward, screen_position.x - width / 2., y, REWARD_FONT_SIZE,
+                TRIM_COLOR.with_alpha(1. - progress * progress));
+        }
+    }
+}
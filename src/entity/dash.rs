@@ -0,0 +1,113 @@
+use macroquad::prelude::*;
+
+const DASH_DURATION: f32 = 0.15; // Duration of a dash in seconds
+const DASH_SPEED: f32 = 360.; // Pixels per second while dashing
+const BASE_COOLDOWN: f32 = 2.; // Seconds needed to recover one charge
+const MIN_COOLDOWN: f32 = 0.6; // Cooldown upgrades can't go below this
+const COOLDOWN_UPGRADE_RATIO: f32 = 0.8; // Each cooldown upgrade removes 20% of the cooldown
+const MAX_CHARGES: u8 = 3;
+const AFTERIMAGE_INTERVAL: f32 = 0.03; // Seconds between two afterimages
+pub(crate) const AFTERIMAGE_LIFETIME: f32 = 0.25; // Seconds before an afterimage disappears
+
+/// Translucent copy of the player left behind while dashing
+pub(crate) struct Afterimage {
+    pub(crate) world_position: Vec2,
+    pub(crate) timer: f32,
+}
+
+/// Short burst of speed during which the player can't be hurt
+pub(crate) struct Dash {
+    pub(crate) max_charges: u8,
+    pub(crate) charges: u8,
+    pub(crate) cooldown: f32,
+    cooldown_timer: f32,
+    direction: Vec2,
+    remaining_time: f32,
+    afterimage_timer: f32,
+    pub(crate) afterimages: Vec<Afterimage>,
+}
+
+impl Dash {
+    pub(crate) fn new() -> Self {
+        Self {
+            max_charges: 1,
+            charges: 1,
+            cooldown: BASE_COOLDOWN,
+            cooldown_timer: 0.,
+            direction: Vec2::ZERO,
+            remaining_time: 0.,
+            afterimage_timer: 0.,
+            afterimages: Vec::new(),
+        }
+    }
+
+    /// Start a dash in the given direction if a charge is available
+    pub(crate) fn start(&mut self, direction: Vec2) {
+        if self.charges == 0 || self.is_dashing() || direction == Vec2::ZERO {
+            return;
+        }
+        self.charges -= 1;
+        self.direction = direction.normalize();
+        self.remaining_time = DASH_DURATION;
+        self.afterimage_timer = 0.;
+    }
+
+    pub(crate) fn is_dashing(&self) -> bool {
+        self.remaining_time > 0.
+    }
+
+    /// Update the dash timers and return the movement of the dash for this frame
+    pub(crate) fn update(&mut self, world_position: Vec2) -> Vec2 {
+        let dt = get_frame_time();
+
+        if self.charges < self.max_charges {
+            self.cooldown_timer += dt;
+            if self.cooldown_timer >= self.cooldown {
+                self.cooldown_timer = 0.;
+                self.charges += 1;
+            }
+        }
+
+        for afterimage in self.afterimages.iter_mut() {
+            afterimage.timer += dt;
+        }
+        self.afterimages.retain(|afterimage| afterimage.timer < AFTERIMAGE_LIFETIME);
+
+        if !self.is_dashing() {
+            return Vec2::ZERO;
+        }
+        self.remaining_time -= dt;
+        self.afterimage_timer -= dt;
+        if self.afterimage_timer <= 0. {
+            self.afterimage_timer = AFTERIMAGE_INTERVAL;
+            self.afterimages.push(Afterimage { world_position, timer: 0. });
+        }
+        self.direction * DASH_SPEED * dt
+    }
+
+    /// Progress of the next charge, from 0 (just used) to 1 (every charge available)
+    pub(crate) fn cooldown_progress(&self) -> f32 {
+        if self.charges >= self.max_charges {
+            1.
+        } else {
+            self.cooldown_timer / self.cooldown
+        }
+    }
+
+    pub(crate) fn can_add_charge(&self) -> bool {
+        self.max_charges < MAX_CHARGES
+    }
+
+    pub(crate) fn add_charge(&mut self) {
+        self.max_charges += 1;
+        self.charges += 1;
+    }
+
+    pub(crate) fn can_reduce_cooldown(&self) -> bool {
+        self.cooldown > MIN_COOLDOWN
+    }
+
+    pub(crate) fn reduce_cooldown(&mut self) {
+        self.cooldown = (self.cooldown * COOLDOWN_UPGRADE_RATIO).max(MIN_COOLDOWN);
+    }
+}
@@ -0,0 +1,113 @@
+use ::rand::{Rng, seq::IndexedRandom};
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{data::{ensure, ensure_non_negative, ensure_positive, load_ron}, entity::pickup::{DropChance, validate_drops}};
+
+pub(crate) const ELITES_PATH: &str = "assets/elites.ron";
+
+/// Modifiers stacked on top of the ennemies spawned as elites, as described in `assets/elites.ron`
+#[derive(Clone, Deserialize)]
+pub(crate) struct EliteDefinitions {
+    /// Chance for a spawned ennemy to be an elite at the start of a run
+    pub(crate) chance: f32,
+    /// Added to the chance for each minute survived
+    pub(crate) chance_per_minute: f32,
+    pub(crate) max_chance: f32,
+    /// Chance for an elite to get one more modifier, rolled again after each extra one
+    pub(crate) extra_modifier_chance: f32,
+    /// Chance for an elite to drop a chest, added to the one of its kind
+    pub(crate) chest_chance: f32,
+    /// Rolled on top of the drops of its kind
+    #[serde(default)]
+    pub(crate) drops: Vec<DropChance>,
+    pub(crate) modifiers: Vec<EliteModifierDefinition>,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct EliteModifierDefinition {
+    pub(crate) modifier: EliteModifier,
+    /// Color of the outline of the elites with the modifier
+    pub(crate) color: [f32; 4],
+    /// Seconds survived before the modifier can be rolled
+    #[serde(default)]
+    pub(crate) min_time: f32,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum EliteModifier {
+    /// Ignores a fraction of the damage taken
+    Armored { damage_reduction: f32 },
+    /// Multiplies the speed
+    Swift { speed: f32 },
+    /// Heals a fraction of its max HP each second
+    Regenerating { hp_per_second: f32 },
+    /// The first hits are absorbed without any damage
+    Shielded { hits: u8 },
+    /// Isn't spent by hitting the player, healing a fraction of the damage dealt instead
+    Vampiric { lifesteal: f32 },
+    /// Splits into smaller ennemies of the same kind when killed, each with a fraction of its max HP
+    Splitting { count: u8, hp: f32 },
+}
+
+impl EliteModifierDefinition {
+    pub(crate) fn color(&self) -> Color {
+        Color::new(self.color[0], self.color[1], self.color[2], self.color[3])
+    }
+}
+
+impl EliteDefinitions {
+    /// Load the elite modifiers, the error naming the faulty field when they are invalid
+    pub(crate) async fn load() -> Result<Self, String> {
+        let definitions: EliteDefinitions = load_ron(ELITES_PATH).await?;
+        definitions.validate().map_err(|error| format!("Invalid {ELITES_PATH}: {error}"))?;
+        Ok(definitions)
+    }
+
+    /// Chance for an ennemy spawned after `time` seconds to be an elite
+    pub(crate) fn chance(&self, time: f32) -> f32 {
+        (self.chance + self.chance_per_minute * time / 60.).min(self.max_chance)
+    }
+
+    /// Roll the modifiers of an ennemy spawned after `time` seconds, none when it isn't an elite.
+    /// An elite doesn't get the same modifier twice.
+    pub(crate) fn roll(&self, time: f32, rng: &mut impl Rng) -> Vec<&EliteModifierDefinition> {
+        let available: Vec<&EliteModifierDefinition> = self.modifiers.iter()
+            .filter(|definition| definition.min_time <= time)
+            .collect();
+        if available.is_empty() || rng.random::<f32>() >= self.chance(time) {
+            return Vec::new();
+        }
+        let mut count = 1;
+        while count < available.len() && rng.random::<f32>() < self.extra_modifier_chance {
+            count += 1;
+        }
+        available.choose_multiple(rng, count).copied().collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (field, chance) in [("chance", self.chance), ("max_chance", self.max_chance),
+            ("extra_modifier_chance", self.extra_modifier_chance), ("chest_chance", self.chest_chance)] {
+            ensure((0. ..=1.).contains(&chance), field, &format!("must be between 0 and 1, got {chance}"))?;
+        }
+        ensure_non_negative(self.chance_per_minute, "chance_per_minute")?;
+        validate_drops(&self.drops, "drops")?;
+        for (i, definition) in self.modifiers.iter().enumerate() {
+            let field = format!("modifiers[{i}].modifier");
+            ensure_non_negative(definition.min_time, &format!("modifiers[{i}].min_time"))?;
+            match definition.modifier {
+                EliteModifier::Armored { damage_reduction } => ensure((0. ..1.).contains(&damage_reduction),
+                    &field, &format!("damage_reduction must be at least 0 and below 1, got {damage_reduction}"))?,
+                EliteModifier::Swift { speed } => ensure_positive(speed, &field)?,
+                EliteModifier::Regenerating { hp_per_second } => ensure_positive(hp_per_second, &field)?,
+                EliteModifier::Shielded { hits } => ensure(hits > 0, &field, "must absorb at least one hit")?,
+                EliteModifier::Vampiric { lifesteal } => ensure_non_negative(lifesteal, &field)?,
+                EliteModifier::Splitting { count, hp } => {
+                    ensure(count > 0, &field, "must split into at least one ennemy")?;
+                    ensure_positive(hp, &field)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
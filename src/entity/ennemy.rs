@@ -1,44 +1,320 @@
 use macroquad::prelude::*;
 
-use crate::{collision::{Collidable, Hitbox, HitboxParams}, entity::character::{CharTextureParams, Character, Direction}};
+use crate::{atlas::Sprite, collision::{Collidable, CollisionLayer, Hitbox}, damage::{DamageSource, DamageTarget, DamageType, Damageable, Resistances}, draw_utils::is_on_screen, entity::{animation::{DEATH, HURT}, boss::BossBrain, character::{Character, Direction}, elite::EliteModifier, ennemy_definition::{EnnemyDefinition, RangedAttack}, status::StatusEffects}, telegraph::Telegraph};
 
-const FRAME_DURATION: f32 = 0.12; // Duration of each animation frame in seconds
-const NB_FRAMES: u8 = 11; // Number of frames in the player animation
-const FRAME_WIDTH: f32 = 64.0; // Width of each frame in the sprite sheet
-const FRAME_HEIGHT: f32 = 64.0; // Height of each frame in the sprite sheet
-const ENNEMY_WIDTH: f32 = 29.0; // Width of the ennemy hitbox
-const ENNEMY_HEIGHT: f32 = 43.0; // Height of the enn
+const DEATH_FADE_DURATION: f32 = 0.4; // Seconds for a dead ennemy to fade out
+const DEATH_MIN_SCALE: f32 = 0.3; // Size left when fading without a death clip
+const CORPSE_COLOR: Color = Color::new(0.25, 0.05, 0.05, 0.6);
+const LATCH_COOLDOWN: f32 = 1.; // Seconds before a vampiric elite touching the player can hurt it again
+const SPLIT_SCALE: f32 = 0.75; // Size of the ennemies split from a splitting elite, relative to it
+const SHIELD_COLOR: Color = Color::new(0.6, 0.85, 1., 0.8);
+const STATUS_INDICATOR_OFFSET: f32 = 6.; // Space between the head of an ennemy and its status effects
 
 pub(crate) struct Ennemy {
     pub(crate) character: Character,
     pub(crate) vel: Vec2,
-    pub(crate) collided: bool,
+    state: EnnemyState,
+    hurt_timer: f32,
+    /// Index of the ennemy definition, which is also the index of its sprite sheets
+    pub(crate) kind: usize,
+    pub(crate) speed: f32,
+    pub(crate) damage: f32,
+    /// HP the ennemy spawned with
+    pub(crate) max_hp: f32,
+    hurt_cooldown: f32,
+    /// Player level when the ennemy spawned, which its stats are scaled for
+    level: u16,
+    corpse_lifetime: f32,
+    /// Attack patterns of the ennemies defined as bosses
+    pub(crate) boss: Option<BossBrain>,
+    /// Seconds before a ranged ennemy can shoot again
+    shot_timer: f32,
+    /// Warning of the shot of a ranged ennemy, with where it shoots at
+    aim: Option<(Telegraph, Vec2)>,
+    /// Modifiers of the elites, stacked on top of their definition
+    elite: Vec<EliteModifier>,
+    /// Color of the outline of the elites
+    outline: Option<Color>,
+    /// Hits left that a shielded elite absorbs
+    shield: u8,
+    /// Seconds before a vampiric elite can hurt the player again
+    latch_timer: f32,
+    pub(crate) statuses: StatusEffects,
+    resistances: Resistances,
+}
+
+pub(crate) enum EnnemyState {
+    Alive,
+    /// Playing the death clip then fading out, or fading and shrinking without a death clip.
+    /// The ennemy has no hitbox anymore.
+    Dying { fade_timer: f32, death_clip: bool, drops: bool },
+    /// Stain left on the ground, fading over time
+    Corpse { timer: f32 },
+    /// Ready to be removed
+    Gone,
 }
 
 impl Ennemy {
-    pub(crate) fn new(pos: Vec2, vel: Vec2) -> Self {
-        let hitbox_params = HitboxParams {
-            size: Vec2 { x: ENNEMY_WIDTH, y: ENNEMY_HEIGHT },
-            offset_frame: Vec2 { x: 0.0, y: 8.0 },
-        };
-        Ennemy {
-            character: Character::new(pos, hitbox_params),
+    /// Spawn an ennemy scaled for the given level of the player
+    pub(crate) fn new(pos: Vec2, vel: Vec2, kind: usize, definition: &EnnemyDefinition, level: u16) -> Self {
+        let mut character = Character::new(pos, definition.hitbox.clone(), definition.animation.clone());
+        let max_hp = definition.hp + definition.per_level.hp * (level - 1) as f32;
+        character.hp = max_hp;
+        let mut ennemy = Ennemy {
+            character,
             vel,
-            collided: false,
+            state: EnnemyState::Alive,
+            hurt_timer: 0.,
+            kind,
+            speed: 0.,
+            damage: 0.,
+            max_hp,
+            hurt_cooldown: 0.,
+            level,
+            corpse_lifetime: 0.,
+            boss: None,
+            shot_timer: 0.,
+            aim: None,
+            elite: Vec::new(),
+            outline: None,
+            shield: 0,
+            latch_timer: 0.,
+            statuses: StatusEffects::default(),
+            resistances: Resistances::default(),
+        };
+        ennemy.apply_definition(definition);
+        ennemy
+    }
+
+    /// Take every value of the definition but the HP, so that a reloaded definition changes the living ennemies
+    pub(crate) fn apply_definition(&mut self, definition: &EnnemyDefinition) {
+        let levels = (self.level - 1) as f32;
+        self.character.hitbox_params = definition.hitbox.clone();
+        self.character.tint = definition.tint();
+        self.character.set_animation(definition.animation.clone());
+        let elite_speed: f32 = self.elite.iter()
+            .map(|modifier| match modifier {
+                EliteModifier::Swift { speed } => *speed,
+                _ => 1.,
+            })
+            .product();
+        self.speed = (definition.speed + definition.per_level.speed * levels) * elite_speed;
+        self.damage = definition.damage + definition.per_level.damage * levels;
+        self.hurt_cooldown = definition.hurt_cooldown;
+        self.corpse_lifetime = definition.corpse_lifetime;
+        self.resistances = definition.resistances;
+        self.character.scale = definition.boss.as_ref().map_or(1., |boss| boss.scale);
+        // A boss keeps going through its pattern, from its current phase
+        match &definition.boss {
+            Some(boss) if self.boss.is_none() => self.boss = Some(BossBrain::new(boss)),
+            Some(_) => (),
+            None => self.boss = None,
+        }
+        if definition.ranged.is_none() {
+            self.aim = None;
         }
     }
     
+    /// Stack elite modifiers on top of the definition, `outline` being the color the elite is outlined with
+    pub(crate) fn make_elite(&mut self, modifiers: Vec<EliteModifier>, outline: Color, definition: &EnnemyDefinition) {
+        self.shield = modifiers.iter()
+            .map(|modifier| match modifier {
+                EliteModifier::Shielded { hits } => *hits,
+                _ => 0,
+            })
+            .sum();
+        self.elite = modifiers;
+        self.outline = Some(outline);
+        self.apply_definition(definition);
+    }
+    
+    pub(crate) fn is_elite(&self) -> bool {
+        !self.elite.is_empty()
+    }
+    
+    /// Turn a new ennemy into a smaller copy split from a splitting elite, with a fraction of the max HP
+    pub(crate) fn shrink(&mut self, hp_ratio: f32) {
+        self.max_hp *= hp_ratio;
+        self.character.hp = self.max_hp;
+        self.character.scale *= SPLIT_SCALE;
+    }
+    
+    /// Number of ennemies a splitting elite splits into when killed, with the fraction of its max HP they get
+    pub(crate) fn split(&self) -> Option<(u8, f32)> {
+        self.elite.iter().find_map(|modifier| match modifier {
+            EliteModifier::Splitting { count, hp } => Some((*count, *hp)),
+            _ => None,
+        })
+    }
+    
+    /// Fraction of the damage dealt to the player that a vampiric elite heals, which isn't spent by its hit
+    pub(crate) fn lifesteal(&self) -> Option<f32> {
+        self.elite.iter().find_map(|modifier| match modifier {
+            EliteModifier::Vampiric { lifesteal } => Some(*lifesteal),
+            _ => None,
+        })
+    }
+    
+    /// Whether a vampiric elite touching the player can hurt it, which it can't again for a moment
+    pub(crate) fn latch(&mut self) -> bool {
+        if self.latch_timer > 0. {
+            return false;
+        }
+        self.latch_timer = LATCH_COOLDOWN;
+        true
+    }
+    
+    pub(crate) fn heal(&mut self, hp: f32) {
+        self.character.hp = (self.character.hp + hp).min(self.max_hp);
+    }
+    
+    pub(crate) fn is_alive(&self) -> bool {
+        matches!(self.state, EnnemyState::Alive)
+    }
+    
+    pub(crate) fn is_gone(&self) -> bool {
+        matches!(self.state, EnnemyState::Gone)
+    }
+    
+    /// Start dying, `drops` telling whether the ennemy leaves its drops once it has faded out
+    pub(crate) fn die(&mut self, drops: bool) {
+        let death_clip = self.character.play(DEATH);
+        self.state = EnnemyState::Dying { fade_timer: 0., death_clip, drops };
+    }
+    
+    /// Advance the death of the ennemy. Returns true when it has faded out and leaves its drops.
+    pub(crate) fn update_death(&mut self) -> bool {
+        let dt = get_frame_time();
+        match &mut self.state {
+            EnnemyState::Alive | EnnemyState::Gone => false,
+            EnnemyState::Dying { fade_timer, death_clip, drops } => {
+                self.character.update_animation();
+                // The fade out starts at the end of the death clip
+                if !*death_clip || !self.character.is_playing(DEATH) {
+                    *fade_timer += dt;
+                }
+                if *fade_timer < DEATH_FADE_DURATION {
+                    return false;
+                }
+                let drops = *drops;
+                self.state = if self.corpse_lifetime > 0. { EnnemyState::Corpse { timer: 0. } } else { EnnemyState::Gone };
+                drops
+            }
+            EnnemyState::Corpse { timer } => {
+                *timer += dt;
+                if *timer >= self.corpse_lifetime {
+                    self.state = EnnemyState::Gone;
+                }
+                false
+            }
+        }
+    }
+    
+    /// Ennemies can't be hit again right after being hit, so weapons staying on them
+    /// like the sword or the aura don't deal damage every frame
+    pub(crate) fn can_be_hit(&self) -> bool {
+        self.hurt_timer <= 0.
+    }
+    
+    /// Walk towards the player until it is in range, then stand still aiming at where it was and shoot.
+    /// Returns the movement of the frame, `walk` when walking, and the target of the shot fired during it.
+    pub(crate) fn update_ranged(&mut self, attack: &RangedAttack, player_position: Vec2, walk: Vec2)
+        -> (Vec2, Option<Vec2>) {
+        self.shot_timer -= get_frame_time();
+        if let Some((telegraph, target)) = &mut self.aim {
+            if !telegraph.update() {
+                return (Vec2::ZERO, None);
+            }
+            let target = *target;
+            self.aim = None;
+            self.shot_timer = attack.cooldown;
+            return (Vec2::ZERO, Some(target));
+        }
+        let position = self.character.world_position;
+        if position.distance(player_position) > attack.range {
+            return (walk, None);
+        }
+        if self.shot_timer <= 0. {
+            self.aim = Some((attack.projectile.aim(position, player_position, attack.aim), player_position));
+        }
+        (Vec2::ZERO, None)
+    }
+    
+    /// Warning of the shot a ranged ennemy is aiming
+    pub(crate) fn aim(&self) -> Option<&Telegraph> {
+        self.aim.as_ref().map(|(telegraph, _)| telegraph)
+    }
+    
+    /// Count the cooldowns down and regenerate the elites, whether the ennemy moves or not
+    pub(crate) fn update(&mut self, dt: f32) {
+        self.hurt_timer -= dt;
+        self.latch_timer -= dt;
+        for modifier in &self.elite {
+            if let EliteModifier::Regenerating { hp_per_second } = modifier {
+                self.character.hp = (self.character.hp + self.max_hp * hp_per_second * dt).min(self.max_hp);
+            }
+        }
+    }
+    
+    /// Stand still without animating, only pushed by the knockback
+    pub(crate) fn update_frozen(&mut self) {
+        self.character.world_position += self.statuses.movement(Vec2::ZERO, get_frame_time());
+    }
+    
     pub(crate) fn move_by(&mut self, movement: Vec2, direction: Direction) {
-        self.character.move_by(movement, direction);
+        self.character.move_by(self.statuses.movement(movement, get_frame_time()), direction);
+        // Ennemies don't react to animation events
+        self.character.update_animation();
     }
     
-    pub(crate) fn draw(&mut self, screen_origin_position: Vec2, texture: &Texture2D) {
-        self.character.draw(texture, texture, &CharTextureParams {
-            frame_duration: FRAME_DURATION,
-            nb_frames: NB_FRAMES,
-            frame_width: FRAME_WIDTH,
-            frame_height: FRAME_HEIGHT,
-        }, screen_origin_position);
+    /// Draw the living and dying ennemy, the elites with their outline and the shielded ones in their bubble
+    pub(crate) fn draw(&self, screen_origin_position: Vec2, sprite: &Sprite, flash_material: Option<&Material>,
+        silhouette_material: &Material) {
+        match self.state {
+            EnnemyState::Alive => {
+                if let Some(outline) = self.outline {
+                    self.character.draw_outline(sprite, screen_origin_position, silhouette_material, outline);
+                }
+                self.character.draw(sprite, screen_origin_position, flash_material);
+                if self.shield > 0 {
+                    let center = self.character.world_position + self.character.hitbox_params.offset_frame
+                        - screen_origin_position;
+                    let radius = self.character.hitbox_params.size.max_element() / 2. + 4.;
+                    // Thicker with more hits to absorb
+                    draw_circle_lines(center.x, center.y, radius, self.shield as f32, SHIELD_COLOR);
+                }
+                let above_head = Vec2 {
+                    x: self.character.world_position.x,
+                    y: self.character.head_y() - STATUS_INDICATOR_OFFSET,
+                };
+                self.statuses.draw_indicators(above_head - screen_origin_position);
+            }
+            EnnemyState::Dying { fade_timer, death_clip, .. } => {
+                let progress = fade_timer / DEATH_FADE_DURATION;
+                let scale = if death_clip { 1. } else { 1. - (1. - DEATH_MIN_SCALE) * progress };
+                self.character.draw_faded(sprite, screen_origin_position, 1. - progress, scale);
+            }
+            EnnemyState::Corpse { .. } | EnnemyState::Gone => (),
+        }
+    }
+    
+    /// Corpses are drawn under every ennemy
+    pub(crate) fn draw_corpse(&self, screen_origin_position: Vec2) {
+        let EnnemyState::Corpse { timer } = self.state else {
+            return;
+        };
+        let hitbox = &self.character.hitbox_params;
+        // The stain is at the feet, the bottom of the hitbox
+        let screen_position = Vec2 {
+            x: self.character.world_position.x + hitbox.offset_frame.x - screen_origin_position.x,
+            y: self.character.world_position.y + hitbox.offset_frame.y + hitbox.size.y / 2. - screen_origin_position.y,
+        };
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        let alpha = CORPSE_COLOR.a * (1. - timer / self.corpse_lifetime);
+        draw_ellipse(screen_position.x, screen_position.y, hitbox.size.x * 0.7, hitbox.size.x * 0.3, 0.,
+            CORPSE_COLOR.with_alpha(alpha));
     }
 }
 
@@ -46,4 +322,47 @@ impl Collidable for Ennemy {
     fn hitbox(&self) -> Hitbox {
         self.character.hitbox()
     }
-}
\ No newline at end of file
+
+    fn layer(&self) -> CollisionLayer {
+        CollisionLayer::Ennemy
+    }
+}
+
+impl Damageable for Ennemy {
+    /// Hits go through the shield of the elites, then the resistances, the armor of the elites and the vulnerability.
+    /// The ticks of the burns and poisons go around the shield and don't stagger the ennemy. A hit never heals.
+    fn take_hit(&mut self, damage: f32, damage_type: DamageType, source: DamageSource) -> (f32, bool) {
+        if source != DamageSource::Status {
+            self.hurt_timer = self.hurt_cooldown;
+            self.character.flash();
+            if self.shield > 0 {
+                self.shield -= 1;
+                return (0., false);
+            }
+            self.character.play(HURT);
+        }
+        let damage = self.elite.iter().fold(self.resistances.apply(damage, damage_type), |damage, modifier| match modifier {
+            EliteModifier::Armored { damage_reduction } => damage * (1. - damage_reduction),
+            _ => damage,
+        }) * self.statuses.vulnerability();
+        let damage = damage.max(0.);
+        self.character.hp -= damage;
+        let kill = self.character.hp <= 0.;
+        if kill {
+            self.die(true);
+        }
+        (damage, kill)
+    }
+
+    fn target(&self) -> DamageTarget {
+        DamageTarget::Ennemy
+    }
+
+    fn hit_position(&self) -> Vec2 {
+        self.character.world_position
+    }
+
+    fn max_hp(&self) -> f32 {
+        self.max_hp
+    }
+}
@@ -0,0 +1,145 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{collision::HitboxParams, damage::{DamageType, Resistances}, data::{ensure, ensure_non_negative, ensure_positive, load_ron}, entity::{animation::AnimationSet, boss_definition::BossDefinition, ennemy_projectile::ProjectileKind, pickup::{DropChance, validate_drops}, status::{StatusApplication, validate_statuses}}};
+
+pub(crate) const ENNEMIES_PATH: &str = "assets/ennemies.ron";
+
+/// A kind of ennemy, as described in `assets/ennemies.ron`
+#[derive(Clone, Deserialize)]
+pub(crate) struct EnnemyDefinition {
+    pub(crate) name: String,
+    pub(crate) animation: AnimationSet,
+    pub(crate) hitbox: HitboxParams,
+    #[serde(default = "default_tint")]
+    pub(crate) tint: [f32; 4],
+    pub(crate) hp: f32,
+    /// Fraction of the player base speed
+    pub(crate) speed: f32,
+    /// Damage dealt to the player when the ennemy touches it
+    pub(crate) damage: f32,
+    /// Effects applied to the player when the ennemy touches it
+    #[serde(default)]
+    pub(crate) on_hit: Vec<StatusApplication>,
+    /// Speed in pixels per second at which the player touched by the ennemy is pushed away, slowing down quickly
+    #[serde(default)]
+    pub(crate) knockback: f32,
+    /// Fraction of the damage of each type ignored, negative for weaknesses
+    #[serde(default)]
+    pub(crate) resistances: Resistances,
+    /// Seconds during which the ennemy can't be hit again
+    pub(crate) hurt_cooldown: f32,
+    /// Player level from which the ennemy starts spawning, bosses spawning on their own timer instead
+    #[serde(default = "default_min_level")]
+    pub(crate) min_level: u16,
+    #[serde(default)]
+    pub(crate) per_level: EnnemyScaling,
+    /// Seconds during which the stain left by a dead ennemy stays, none is left when 0
+    #[serde(default)]
+    pub(crate) corpse_lifetime: f32,
+    /// Ennemies shooting at the player stop once they are in range
+    #[serde(default)]
+    pub(crate) ranged: Option<RangedAttack>,
+    /// Bosses get a health bar at the bottom of the screen and always drop a chest
+    #[serde(default)]
+    pub(crate) boss: Option<BossDefinition>,
+    /// Chance to drop a chest when killed
+    #[serde(default)]
+    pub(crate) chest_chance: f32,
+    /// Pickups that can drop when the ennemy is killed, besides its experience gem
+    #[serde(default)]
+    pub(crate) drops: Vec<DropChance>,
+}
+
+/// Shots of the ennemies attacking from afar
+#[derive(Clone, Deserialize)]
+pub(crate) struct RangedAttack {
+    pub(crate) projectile: ProjectileKind,
+    /// Distance from the player under which the ennemy stops to shoot
+    pub(crate) range: f32,
+    /// Seconds between two shots
+    pub(crate) cooldown: f32,
+    /// Seconds the ennemy stands still aiming, warning of the shot, before it shoots
+    pub(crate) aim: f32,
+    pub(crate) damage: f32,
+    #[serde(default)]
+    pub(crate) damage_type: DamageType,
+    /// Effects applied to the player hit by a shot
+    #[serde(default)]
+    pub(crate) effects: Vec<StatusApplication>,
+}
+
+/// Bonus of the ennemies spawned for each level of the player after the first one
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct EnnemyScaling {
+    pub(crate) hp: f32,
+    pub(crate) speed: f32,
+    pub(crate) damage: f32,
+}
+
+impl EnnemyDefinition {
+    pub(crate) fn tint(&self) -> Color {
+        Color::new(self.tint[0], self.tint[1], self.tint[2], self.tint[3])
+    }
+
+    /// `ennemies` are the names of the kinds of ennemy bosses can summon
+    fn validate(&self, ennemies: &[&str]) -> Result<(), String> {
+        let name = &self.name;
+        self.animation.validate(name)?;
+        ensure_positive(self.hitbox.size.x, &format!("{name}.hitbox.size.x"))?;
+        ensure_positive(self.hitbox.size.y, &format!("{name}.hitbox.size.y"))?;
+        ensure_positive(self.hp, &format!("{name}.hp"))?;
+        ensure_non_negative(self.speed, &format!("{name}.speed"))?;
+        ensure_non_negative(self.damage, &format!("{name}.damage"))?;
+        validate_statuses(&self.on_hit, &format!("{name}.on_hit"))?;
+        ensure_non_negative(self.knockback, &format!("{name}.knockback"))?;
+        self.resistances.validate(&format!("{name}.resistances"))?;
+        ensure_non_negative(self.hurt_cooldown, &format!("{name}.hurt_cooldown"))?;
+        ensure(self.min_level >= 1, &format!("{name}.min_level"), "must be at least 1")?;
+        ensure_non_negative(self.per_level.hp, &format!("{name}.per_level.hp"))?;
+        ensure_non_negative(self.per_level.speed, &format!("{name}.per_level.speed"))?;
+        ensure_non_negative(self.per_level.damage, &format!("{name}.per_level.damage"))?;
+        ensure_non_negative(self.corpse_lifetime, &format!("{name}.corpse_lifetime"))?;
+        ensure(self.chest_chance >= 0. && self.chest_chance <= 1., &format!("{name}.chest_chance"),
+            &format!("must be between 0 and 1, got {}", self.chest_chance))?;
+        validate_drops(&self.drops, &format!("{name}.drops"))?;
+        if let Some(ranged) = &self.ranged {
+            ranged.projectile.validate(&format!("{name}.ranged.projectile"))?;
+            ensure_positive(ranged.range, &format!("{name}.ranged.range"))?;
+            ensure_non_negative(ranged.cooldown, &format!("{name}.ranged.cooldown"))?;
+            ensure_non_negative(ranged.aim, &format!("{name}.ranged.aim"))?;
+            ensure_non_negative(ranged.damage, &format!("{name}.ranged.damage"))?;
+            validate_statuses(&ranged.effects, &format!("{name}.ranged.effects"))?;
+        }
+        match &self.boss {
+            Some(boss) => boss.validate(name, ennemies),
+            None => Ok(()),
+        }
+    }
+}
+
+fn default_tint() -> [f32; 4] {
+    [1., 1., 1., 1.]
+}
+
+fn default_min_level() -> u16 {
+    1
+}
+
+/// Load every kind of ennemy, the error naming the faulty field when they are invalid
+pub(crate) async fn load_ennemy_definitions() -> Result<Vec<EnnemyDefinition>, String> {
+    let definitions: Vec<EnnemyDefinition> = load_ron(ENNEMIES_PATH).await?;
+    // Ennemies must be able to spawn from the start of a run
+    if !definitions.iter().any(|definition| definition.boss.is_none() && definition.min_level == 1) {
+        return Err(format!("Invalid {ENNEMIES_PATH}: at least one ennemy that isn't a boss needs a min_level of 1"));
+    }
+    let ennemies: Vec<&str> = definitions.iter()
+        .filter(|definition| definition.boss.is_none())
+        .map(|definition| definition.name.as_str())
+        .collect();
+    for definition in &definitions {
+        definition.validate(&ennemies).map_err(|error| format!("Invalid {ENNEMIES_PATH}: {error}"))?;
+    }
+    Ok(definitions)
+}
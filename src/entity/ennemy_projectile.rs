@@ -0,0 +1,170 @@
+use std::f32::consts::PI;
+
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{collision::{Collidable, CollisionLayer, Hitbox}, damage::DamageType, data::{ensure_non_negative, ensure_positive}, draw_utils::is_on_screen, entity::status::StatusApplication, telegraph::{Telegraph, TelegraphShape}};
+
+const PROJECTILE_LIFETIME: f32 = 6.; // Seconds before an arrow or a bolt that missed disappears
+const ARROW_LENGTH: f32 = 14.;
+const ARROW_RADIUS: f32 = 3.; // Radius of the hitbox at the tip of an arrow
+const BOLT_RADIUS: f32 = 5.;
+const BOMB_RADIUS: f32 = 6.;
+const LOB_HEIGHT: f32 = 60.; // Height reached by a lobbed bomb halfway through its flight
+const AIM_LENGTH: f32 = 200.; // Length of the lane warning of an arrow or a bolt
+const AIM_WIDTH: f32 = 6.;
+const ARROW_COLOR: Color = Color::new(0.45, 0.3, 0.15, 1.);
+const ARROW_HEAD_COLOR: Color = Color::new(0.75, 0.75, 0.8, 1.);
+const BOLT_COLOR: Color = Color::new(0.8, 0.2, 1., 1.);
+const BOLT_CORE_COLOR: Color = Color::new(1., 0.85, 1., 1.);
+const BOMB_COLOR: Color = Color::new(0.15, 0.15, 0.2, 1.);
+const FUSE_COLOR: Color = Color::new(1., 0.6, 0.1, 1.);
+const SHADOW_COLOR: Color = Color::new(0., 0., 0., 0.3);
+pub(crate) const MARKER_COLOR: Color = Color::new(1., 0.3, 0.1, 0.3);
+
+/// What an ennemy shoots, speeds being distances travelled each frame
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum ProjectileKind {
+    /// Fast and straight
+    Arrow { speed: f32 },
+    /// Turning towards the player by `homing` radians each second
+    Bolt { speed: f32, homing: f32 },
+    /// Lobbed over everything at where the player stood, landing after `flight_time` seconds
+    /// and hitting everything within `radius`
+    Bomb { flight_time: f32, radius: f32 },
+}
+
+impl ProjectileKind {
+    /// Warning of a shot from `position` at `target`, shown while the ennemy aims
+    pub(crate) fn aim(&self, position: Vec2, target: Vec2, duration: f32) -> Telegraph {
+        match self {
+            ProjectileKind::Arrow { .. } | ProjectileKind::Bolt { .. } => {
+                let direction = (target - position).normalize_or(Vec2 { x: 0., y: 1. });
+                Telegraph::new(position, TelegraphShape::Line { direction, length: AIM_LENGTH, width: AIM_WIDTH }, duration)
+            }
+            ProjectileKind::Bomb { radius, .. } => Telegraph::new(target, TelegraphShape::Circle { radius: *radius }, duration),
+        }
+    }
+
+    /// `field` names the projectile in the errors
+    pub(crate) fn validate(&self, field: &str) -> Result<(), String> {
+        match self {
+            ProjectileKind::Arrow { speed } => ensure_positive(*speed, &format!("{field}.speed")),
+            ProjectileKind::Bolt { speed, homing } => {
+                ensure_positive(*speed, &format!("{field}.speed"))?;
+                ensure_non_negative(*homing, &format!("{field}.homing"))
+            }
+            ProjectileKind::Bomb { flight_time, radius } => {
+                ensure_positive(*flight_time, &format!("{field}.flight_time"))?;
+                ensure_positive(*radius, &format!("{field}.radius"))
+            }
+        }
+    }
+}
+
+/// Projectile fired by an ennemy, on its own collision layer
+pub(crate) struct EnnemyProjectile {
+    pub(crate) world_position: Vec2,
+    kind: ProjectileKind,
+    /// Distance travelled each frame by the arrows and the bolts
+    velocity: Vec2,
+    pub(crate) damage: f32,
+    pub(crate) damage_type: DamageType,
+    /// Effects applied to the player hit by the projectile
+    pub(crate) effects: Vec<StatusApplication>,
+    age: f32,
+    /// Where a bomb lands, the marker filling up as it falls. Arrows and bolts don't have any.
+    marker: Option<Telegraph>,
+}
+
+impl EnnemyProjectile {
+    /// Shoot from `world_position` at `target`
+    pub(crate) fn new(world_position: Vec2, target: Vec2, kind: ProjectileKind, damage: f32,
+        damage_type: DamageType, effects: Vec<StatusApplication>) -> Self {
+        let direction = (target - world_position).normalize_or(Vec2 { x: 0., y: 1. });
+        let (velocity, marker) = match kind {
+            ProjectileKind::Arrow { speed } | ProjectileKind::Bolt { speed, .. } => (direction * speed, None),
+            ProjectileKind::Bomb { flight_time, radius } => {
+                (Vec2::ZERO, Some(Telegraph::new(target, TelegraphShape::Circle { radius }, flight_time)))
+            }
+        };
+        Self { world_position, kind, velocity, damage, damage_type, effects, age: 0., marker }
+    }
+
+    /// Move the projectile, bolts turning towards the player.
+    /// Returns true when a bomb lands, which then hits what is in its marker.
+    pub(crate) fn update(&mut self, player_position: Vec2) -> bool {
+        let dt = get_frame_time();
+        self.age += dt;
+        // A bolt right on the player has no direction to turn to
+        let to_player = (player_position - self.world_position).normalize_or_zero();
+        if let ProjectileKind::Bolt { homing, .. } = self.kind && to_player != Vec2::ZERO {
+            let turn = self.velocity.angle_between(to_player).clamp(-homing * dt, homing * dt);
+            self.velocity = Vec2::from_angle(turn).rotate(self.velocity);
+        }
+        self.world_position += self.velocity;
+        self.marker.as_mut().is_some_and(Telegraph::update)
+    }
+
+    /// Bombs fly over everything, they only hit once they land
+    pub(crate) fn is_airborne(&self) -> bool {
+        self.marker.is_some()
+    }
+
+    pub(crate) fn marker(&self) -> Option<&Telegraph> {
+        self.marker.as_ref()
+    }
+
+    pub(crate) fn is_expired(&self) -> bool {
+        match &self.marker {
+            Some(marker) => marker.is_done(),
+            None => self.age >= PROJECTILE_LIFETIME,
+        }
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2) {
+        if let Some(marker) = &self.marker {
+            // Along a parabola from the thrower to the marker, its shadow on the ground
+            let progress = marker.progress();
+            let ground = self.world_position.lerp(marker.world_position, progress) - screen_origin_position;
+            if !is_on_screen(ground) {
+                return;
+            }
+            let y = ground.y - (progress * PI).sin() * LOB_HEIGHT;
+            draw_ellipse(ground.x, ground.y, BOMB_RADIUS, BOMB_RADIUS / 2., 0., SHADOW_COLOR);
+            draw_circle(ground.x, y, BOMB_RADIUS, BOMB_COLOR);
+            draw_line(ground.x + 3., y - 4., ground.x + 6., y - 9., 2., FUSE_COLOR);
+            return;
+        }
+        let screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        if let ProjectileKind::Arrow { .. } = self.kind {
+            let tail = screen_position - self.velocity.normalize_or_zero() * ARROW_LENGTH;
+            draw_line(tail.x, tail.y, screen_position.x, screen_position.y, 2., ARROW_COLOR);
+            draw_circle(screen_position.x, screen_position.y, 2., ARROW_HEAD_COLOR);
+        } else {
+            draw_circle(screen_position.x, screen_position.y, BOLT_RADIUS, BOLT_COLOR);
+            draw_circle(screen_position.x, screen_position.y, BOLT_RADIUS / 2., BOLT_CORE_COLOR);
+        }
+    }
+}
+
+impl Collidable for EnnemyProjectile {
+    /// The hitbox of a bomb is where it lands
+    fn hitbox(&self) -> Hitbox {
+        let circle = match (&self.kind, &self.marker) {
+            (ProjectileKind::Bomb { radius, .. }, Some(marker)) => {
+                Circle::new(marker.world_position.x, marker.world_position.y, *radius)
+            }
+            (ProjectileKind::Arrow { .. }, _) => Circle::new(self.world_position.x, self.world_position.y, ARROW_RADIUS),
+            _ => Circle::new(self.world_position.x, self.world_position.y, BOLT_RADIUS),
+        };
+        Hitbox::Circle(circle)
+    }
+
+    fn layer(&self) -> CollisionLayer {
+        CollisionLayer::EnnemyProjectile
+    }
+}
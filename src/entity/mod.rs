@@ -1,3 +1,18 @@
 pub mod ennemy;
 pub mod player;
-pub mod character;
\ No newline at end of file
+pub mod character;
+pub mod dash;
+pub mod elite;
+pub mod stats;
+pub mod status;
+pub mod xp_gem;
+pub mod character_definition;
+pub mod ennemy_definition;
+pub mod ennemy_projectile;
+pub mod animation;
+pub mod boss;
+pub mod boss_definition;
+pub mod chest;
+pub mod pickup;
+pub mod prop;
+pub mod prop_definition;
@@ -0,0 +1,176 @@
+use ::rand::Rng;
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{data::{ensure, ensure_positive}, draw_utils::is_on_screen};
+
+const PICKUP_SPEED: f32 = 240.; // Pixels per second towards the player once attracted
+const PICKUP_DISTANCE: f32 = 14.; // Distance from the player at which a pickup is collected
+const PICKUP_SIZE: f32 = 6.;
+const PICKUP_LIFETIME: f32 = 40.; // Seconds before a pickup lying on the ground disappears
+const BLINK_DURATION: f32 = 5.; // Pickups blink during their last seconds
+const BLINK_SPEED: f32 = 8.; // Blinks per second
+const MERGE_DISTANCE: f32 = 50.; // Gold further than this from the new coins isn't merged with them
+const FOOD_COLOR: Color = Color::new(0.85, 0.15, 0.15, 1.);
+const LEAF_COLOR: Color = Color::new(0.2, 0.7, 0.2, 1.);
+const MAGNET_COLOR: Color = Color::new(0.8, 0.1, 0.1, 1.);
+const BOMB_COLOR: Color = Color::new(0.15, 0.15, 0.2, 1.);
+const FUSE_COLOR: Color = Color::new(1., 0.6, 0.1, 1.);
+const FREEZE_COLOR: Color = Color::new(0.6, 0.85, 1., 1.);
+const GOLD_COLOR: Color = Color::new(1., 0.8, 0.1, 1.);
+
+/// Effect of a pickup once collected
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub(crate) enum PickupKind {
+    /// Heals that many HP
+    Food(f32),
+    /// Attracts every experience gem of the map
+    Magnet,
+    /// Kills the ennemies on the screen
+    Bomb,
+    /// Stops the ennemies for a while
+    Freeze,
+    /// That many gold coins
+    Gold(u32),
+}
+
+/// Entry of a drop table, rolled independently of the other entries
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) struct DropChance {
+    pub(crate) pickup: PickupKind,
+    /// Chance to drop the pickup, before luck
+    pub(crate) chance: f32,
+}
+
+/// Roll every entry of a drop table, luck making each drop more likely
+pub(crate) fn roll_drops(table: &[DropChance], luck: f32, rng: &mut impl Rng) -> Vec<PickupKind> {
+    table.iter()
+        .filter(|entry| rng.random::<f32>() < entry.chance * luck)
+        .map(|entry| entry.pickup)
+        .collect()
+}
+
+/// Check the entries of a drop table, `field` naming the table in the errors
+pub(crate) fn validate_drops(table: &[DropChance], field: &str) -> Result<(), String> {
+    for (i, entry) in table.iter().enumerate() {
+        ensure(entry.chance >= 0. && entry.chance <= 1., &format!("{field}[{i}].chance"),
+            &format!("must be between 0 and 1, got {}", entry.chance))?;
+        match entry.pickup {
+            PickupKind::Food(hp) => ensure_positive(hp, &format!("{field}[{i}].pickup"))?,
+            PickupKind::Gold(amount) => ensure(amount > 0, &format!("{field}[{i}].pickup"), "must give some gold")?,
+            PickupKind::Magnet | PickupKind::Bomb | PickupKind::Freeze => (),
+        }
+    }
+    Ok(())
+}
+
+/// Item lying on the ground, collected when the player walks on it
+pub(crate) struct Pickup {
+    pub(crate) world_position: Vec2,
+    pub(crate) kind: PickupKind,
+    /// Once attracted, a pickup flies to the player until it is collected
+    attracted: bool,
+    age: f32,
+}
+
+impl Pickup {
+    pub(crate) fn new(world_position: Vec2, kind: PickupKind) -> Self {
+        Self {
+            world_position,
+            kind,
+            attracted: false,
+            age: 0.,
+        }
+    }
+
+    /// Move the pickup towards the player when it is in the magnet radius.
+    /// Returns true when the pickup reaches the player.
+    pub(crate) fn update(&mut self, player_position: Vec2, magnet_radius: f32) -> bool {
+        let dt = get_frame_time();
+        self.age += dt;
+        let to_player = player_position - self.world_position;
+        let distance = to_player.length();
+        if distance < PICKUP_DISTANCE {
+            return true;
+        }
+        if distance < magnet_radius {
+            self.attracted = true;
+        }
+        if self.attracted {
+            self.world_position += to_player / distance * (PICKUP_SPEED * dt).min(distance);
+        }
+        false
+    }
+
+    /// Pickups left on the ground for too long disappear, unless they are flying to the player
+    pub(crate) fn is_expired(&self) -> bool {
+        !self.attracted && self.age >= PICKUP_LIFETIME
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2) {
+        let screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        let time_left = PICKUP_LIFETIME - self.age;
+        if !self.attracted && time_left < BLINK_DURATION && (time_left * BLINK_SPEED).fract() < 0.5 {
+            return;
+        }
+        let (x, y) = (screen_position.x, screen_position.y);
+        match self.kind {
+            PickupKind::Food(_) => {
+                draw_circle(x, y, PICKUP_SIZE, FOOD_COLOR);
+                draw_ellipse(x + 2., y - PICKUP_SIZE, 3., 1.5, -0.5, LEAF_COLOR);
+            }
+            PickupKind::Magnet => {
+                // Horseshoe with white tips
+                draw_rectangle(x - PICKUP_SIZE, y - PICKUP_SIZE, 4., PICKUP_SIZE * 2., MAGNET_COLOR);
+                draw_rectangle(x + PICKUP_SIZE - 4., y - PICKUP_SIZE, 4., PICKUP_SIZE * 2., MAGNET_COLOR);
+                draw_rectangle(x - PICKUP_SIZE, y + PICKUP_SIZE - 4., PICKUP_SIZE * 2., 4., MAGNET_COLOR);
+                draw_rectangle(x - PICKUP_SIZE, y - PICKUP_SIZE, 4., 3., WHITE);
+                draw_rectangle(x + PICKUP_SIZE - 4., y - PICKUP_SIZE, 4., 3., WHITE);
+            }
+            PickupKind::Bomb => {
+                draw_circle(x, y, PICKUP_SIZE, BOMB_COLOR);
+                draw_line(x + 3., y - 4., x + 6., y - 9., 2., FUSE_COLOR);
+            }
+            PickupKind::Freeze => {
+                // Clock face with its hands
+                draw_circle(x, y, PICKUP_SIZE, FREEZE_COLOR);
+                draw_line(x, y, x, y - PICKUP_SIZE + 2., 1., BLACK);
+                draw_line(x, y, x + PICKUP_SIZE - 3., y, 1., BLACK);
+            }
+            PickupKind::Gold(amount) => {
+                // Bigger piles of gold are bigger coins
+                let radius = PICKUP_SIZE * 0.6 * (1. + (amount as f32).log10() * 0.5);
+                draw_circle(x, y, radius, GOLD_COLOR);
+                draw_circle_lines(x, y, radius, 1., ORANGE);
+            }
+        }
+    }
+}
+
+/// Add a pickup to the ground, keeping at most `max_pickups`: gold is merged into the closest gold on
+/// the ground, and otherwise the oldest pickup lying on the ground disappears
+pub(crate) fn add_pickup(pickups: &mut Vec<Pickup>, pickup: Pickup, max_pickups: usize) {
+    if pickups.len() < max_pickups {
+        pickups.push(pickup);
+        return;
+    }
+    if let PickupKind::Gold(amount) = pickup.kind {
+        let closest_gold = pickups.iter_mut()
+            .filter(|other| matches!(other.kind, PickupKind::Gold(_))
+                && other.world_position.distance(pickup.world_position) < MERGE_DISTANCE)
+            .min_by(|a, b| a.world_position.distance_squared(pickup.world_position)
+                .total_cmp(&b.world_position.distance_squared(pickup.world_position)));
+        if let Some(other) = closest_gold && let PickupKind::Gold(other_amount) = &mut other.kind {
+            *other_amount += amount;
+            return;
+        }
+    }
+    // Pickups are pushed in order, the first ones lying on the ground are the oldest
+    if let Some(oldest) = pickups.iter().position(|other| !other.attracted) {
+        pickups.remove(oldest);
+        pickups.push(pickup);
+    }
+}
@@ -1,78 +1,253 @@
-use std::u8;
-
 use macroquad::prelude::*;
 
-use crate::{collision::{Collidable, Hitbox, HitboxParams, hitbox_intersects}, entity::character::{CharTextureParams, Character, Direction}, weapons::{aura::Aura, dagger::DaggerAggregate, sword::Sword}};
+use crate::{asset_manager::{AssetManager, SpriteHandle}, collision::{Collidable, CollisionLayer, Hitbox}, damage::{DamageSource, DamageTarget, DamageType, Damageable}, entity::{animation::{ATTACK, HURT}, character::{Character, Direction}, character_definition::CharacterDefinition, dash::{AFTERIMAGE_LIFETIME, Dash}, stats::{StatBonus, Stats}, status::{StatusApplication, StatusEffect, StatusEffects}}, items::passive::{MAX_PASSIVE_LEVEL, MAX_PASSIVES, PassiveItem}, progression::Upgrade, render_queue::{Layer, RenderQueue}, weapons::{weapon_definition::EvolutionDefinition, weapon_slot::{MAX_WEAPONS, WeaponHit, WeaponKind, WeaponSlot}}};
 
-const FRAME_DURATION: f32 = 0.12; // Duration of each animation frame in seconds
-const NB_FRAMES: u8 = 8; // Number of frames in the player animation
-const FRAME_WIDTH: f32 = 192.0; // Width of each frame in the sprite sheet
-const FRAME_HEIGHT: f32 = 192.0; // Height of each frame in the sprite sheet
-const PLAYER_WIDTH: f32 = 27.0; // Width of the player hitbox
-const PLAYER_HEIGHT: f32 = 48.0; // Height of the player hitbox
+const AFTERIMAGE_COLOR: Color = Color::new(0.6, 0.8, 1.0, 0.5);
+const THROW_EVENT: &str = "throw"; // Event of the attack clip on which the daggers are thrown
+const BRIMSTONE_BURN: StatusEffect = StatusEffect::Burn { damage: 2. }; // Burn of the weapon hits with the brimstone
+const BRIMSTONE_DURATION: f32 = 3.;
+const VENOM_POISON: StatusEffect = StatusEffect::Poison { damage: 1. }; // Poison of the weapon hits with the venom
+const VENOM_DURATION: f32 = 4.;
 
 pub(crate) struct Player {
     pub(crate) character: Character,
-    sword: Sword,
-    daggers: DaggerAggregate,
-    aura: Aura,
+    /// Sprite sheets of the clips, in the order of the clips of the character animation
+    clip_sprites: Vec<SpriteHandle>,
+    /// Direction of the daggers thrown when the attack clip reaches its throw event
+    pending_throw: Option<Vec2>,
+    pub(crate) weapons: Vec<WeaponSlot>,
+    pub(crate) dash: Dash,
+    /// Last movement direction, used to dash when the player stands still
+    pub(crate) facing: Vec2,
+    base_stats: Stats,
+    /// Final stats: the base stats with every passive item applied
+    pub(crate) stats: Stats,
+    /// Passive items owned by the player, with their level
+    pub(crate) passives: Vec<(PassiveItem, u8)>,
+    /// Unique passive of the character, gained again at each level
+    level_bonus: StatBonus,
+    level: u16,
+    pub(crate) statuses: StatusEffects,
 }
 
 impl Player {
-    pub(crate) fn new(pos: Vec2, sword: Sword, daggers: DaggerAggregate) -> Self {
-        let hitbox_params = HitboxParams {
-            size: Vec2 { x: PLAYER_WIDTH, y: PLAYER_HEIGHT },
-            offset_frame: Vec2 { x: 0.0, y: 6.0 },
-        };
-
-        let character = Character::new(
+    pub(crate) fn new(pos: Vec2, definition: &CharacterDefinition, starting_weapon: WeaponSlot,
+        clip_sprites: Vec<SpriteHandle>) -> Self {
+        let mut character = Character::new(
             pos, 
-            hitbox_params
+            definition.hitbox.clone(),
+            definition.animation.clone(),
         );
+        let base_stats = definition.base_stats;
+        character.hp = base_stats.max_hp;
+        character.tint = definition.tint();
         
         Player {
             character,
-            sword,
-            daggers,
-            aura: Aura { circle: Circle::new(pos.x, pos.y, 100.0) },
+            clip_sprites,
+            pending_throw: None,
+            weapons: vec![starting_weapon],
+            dash: Dash::new(),
+            facing: Vec2 { x: 0., y: 1. },
+            base_stats,
+            stats: base_stats,
+            passives: Vec::new(),
+            level_bonus: definition.passive.level_bonus,
+            level: 1,
+            statuses: StatusEffects::default(),
+        }
+    }
+    
+    /// Use a reloaded character definition, keeping the current HP
+    pub(crate) fn apply_definition(&mut self, definition: &CharacterDefinition,
+        clip_sprites: Vec<SpriteHandle>) {
+        self.character.hitbox_params = definition.hitbox.clone();
+        self.character.tint = definition.tint();
+        self.character.set_animation(definition.animation.clone());
+        self.clip_sprites = clip_sprites;
+        self.base_stats = definition.base_stats;
+        self.level_bonus = definition.passive.level_bonus;
+        self.update_stats();
+        self.character.hp = self.character.hp.min(self.stats.max_hp);
+    }
 
+    /// Update the player and its weapons, `targets` being the positions of the ennemies they can aim at
+    pub(crate) fn udpate(&mut self, targets: &[Vec2]) {
+        for weapon in self.weapons.iter_mut() {
+            weapon.update(self.character.world_position, &self.stats, targets);
+        }
+        self.character.hp = (self.character.hp + self.stats.regen * get_frame_time()).min(self.stats.max_hp);
+        
+        let events = self.character.update_animation();
+        // The daggers are also thrown when the attack clip was cut before its event
+        let throw = events.iter().any(|event| event == THROW_EVENT) || !self.character.is_playing(ATTACK);
+        if throw && let Some(direction) = self.pending_throw.take() {
+            self.throw_dagger(direction);
         }
     }
     
-    pub(crate) fn udpate(&mut self) {
-        self.sword.update();
-        self.daggers.update();
-        self.aura.circle.x = self.character.world_position.x;
-        self.aura.circle.y = self.character.world_position.y;
+    pub(crate) fn heal(&mut self, hp: f32) {
+        self.character.hp = (self.character.hp + hp).min(self.stats.max_hp);
     }
     
-    pub(crate) fn draw(&mut self, screen_origin_position: Vec2, idle_texture: &Texture2D, walking_texture: &Texture2D) {
-        self.aura.draw(screen_origin_position);
-        self.character.draw(idle_texture, walking_texture, &CharTextureParams {
-            frame_duration: FRAME_DURATION,
-            nb_frames: NB_FRAMES,
-            frame_width: FRAME_WIDTH,
-            frame_height: FRAME_HEIGHT,
-        }, screen_origin_position);
-        self.sword.draw(screen_origin_position);
-        self.daggers.draw(screen_origin_position);
+    /// Queue the player, its afterimages and its weapons: the aura lies on the ground,
+    /// the sword and the daggers fly above the characters
+    pub(crate) fn queue_draw<'a>(&'a self, queue: &mut RenderQueue<'a>, screen_origin_position: Vec2,
+        assets: &'a AssetManager, draw_afterimages: bool, flash_material: Option<&'a Material>) {
+        let sprite_handle = self.clip_sprites[self.character.clip()];
+        if draw_afterimages {
+            // Afterimages are at the position the player had, so they are sorted like it was there
+            let foot_offset = self.character.foot_y() - self.character.world_position.y;
+            for afterimage in &self.dash.afterimages {
+                let alpha = AFTERIMAGE_COLOR.a * (1. - afterimage.timer / AFTERIMAGE_LIFETIME);
+                queue.push(Layer::Actors, afterimage.world_position.y + foot_offset, move || {
+                    self.character.draw_ghost(&assets.sprite(sprite_handle), screen_origin_position,
+                        afterimage.world_position, AFTERIMAGE_COLOR.with_alpha(alpha));
+                });
+            }
+        }
+        queue.push(Layer::Actors, self.character.foot_y(), move || {
+            self.character.draw(&assets.sprite(sprite_handle), screen_origin_position, flash_material);
+        });
+        for weapon in &self.weapons {
+            let layer = match weapon {
+                WeaponSlot::Aura(_) => Layer::Decals,
+                _ => Layer::Projectiles,
+            };
+            if let WeaponSlot::Daggers(daggers) = weapon {
+                queue.push(Layer::Shadows, 0., move || daggers.draw_shadows(screen_origin_position));
+            }
+            queue.push(layer, 0., move || weapon.draw(screen_origin_position, assets));
+        }
     }
     
-    pub(crate) fn weapons_collides_with(&mut self, hitbox: &Hitbox) -> bool {
-        let weapon_hitbox = self.sword.hitbox();
-        
-        hitbox_intersects(&weapon_hitbox, hitbox) || 
-        self.daggers.collide_with(hitbox) || 
-        hitbox_intersects(&self.aura.hitbox(), hitbox)
+    /// Hit of the weapon in `slot` if it touches the target, with the effects of the passive items.
+    /// A dagger is consumed by its hit, so the weapons are asked one by one until the target dies.
+    pub(crate) fn weapon_hit(&mut self, slot: usize, target: &impl Collidable) -> Option<WeaponHit> {
+        let stats = self.stats;
+        let mut hit = self.weapons[slot].hit(target, &stats)?;
+        for (effect, duration, chance) in [(BRIMSTONE_BURN, BRIMSTONE_DURATION, stats.burn_chance),
+            (VENOM_POISON, VENOM_DURATION, stats.poison_chance)] {
+            if chance > 0. {
+                hit.effects.push(StatusApplication { effect, duration, chance: chance.min(1.) });
+            }
+        }
+        Some(hit)
     }
     
     pub(crate) fn move_by(&mut self, movement: Vec2, player_direction: Direction) {
+        if movement != Vec2::ZERO {
+            self.facing = movement.normalize();
+        }
         self.character.move_by(movement, player_direction);
-        self.sword.weapon.world_position = self.character.world_position;
     }
 
-    pub(crate) fn throw_dagger(&mut self, vel: Vec2, angle: f32) {
-        self.daggers.new_dagger(self.character.world_position, vel, angle, 0.07);
+    /// The player can't be hurt while dashing
+    pub(crate) fn is_invulnerable(&self) -> bool {
+        self.dash.is_dashing()
+    }
+
+    pub(crate) fn can_apply(&self, upgrade: Upgrade) -> bool {
+        match upgrade {
+            Upgrade::DashCharge => self.dash.can_add_charge(),
+            Upgrade::DashCooldown => self.dash.can_reduce_cooldown(),
+            Upgrade::Passive(item) => match self.passive_level(item) {
+                0 => self.passives.len() < MAX_PASSIVES,
+                level => level < MAX_PASSIVE_LEVEL,
+            },
+            Upgrade::Weapon(kind) => match self.weapons.iter().find(|weapon| weapon.kind() == kind) {
+                Some(weapon) => weapon.level() < weapon.max_level(),
+                None => self.weapons.len() < MAX_WEAPONS,
+            },
+        }
+    }
+
+    pub(crate) fn apply_upgrade(&mut self, upgrade: Upgrade) {
+        match upgrade {
+            Upgrade::DashCharge => self.dash.add_charge(),
+            Upgrade::DashCooldown => self.dash.reduce_cooldown(),
+            Upgrade::Passive(item) => {
+                match self.passives.iter_mut().find(|(owned, _)| *owned == item) {
+                    Some((_, level)) => *level += 1,
+                    None => self.passives.push((item, 1)),
+                }
+                self.update_stats();
+            }
+            // New weapons are built by the game, which owns their textures, then given with `add_weapon`
+            Upgrade::Weapon(kind) => {
+                if let Some(weapon) = self.weapons.iter_mut().find(|weapon| weapon.kind() == kind) {
+                    weapon.level_up();
+                }
+            }
+        }
+    }
+
+    /// The evolution needs its weapon at its maximum level, not evolved yet, and its passive item
+    pub(crate) fn can_evolve(&self, evolution: &EvolutionDefinition) -> bool {
+        self.passive_level(evolution.passive) > 0 && self.weapons.iter().any(|weapon| weapon.kind() == evolution.weapon
+            && weapon.level() >= weapon.max_level() && weapon.evolution().is_none())
+    }
+
+    pub(crate) fn evolve(&mut self, evolution: &EvolutionDefinition) {
+        if let Some(weapon) = self.weapons.iter_mut().find(|weapon| weapon.kind() == evolution.weapon) {
+            weapon.set_evolution(Some(evolution.clone()));
+        }
+    }
+
+    pub(crate) fn has_weapon(&self, kind: WeaponKind) -> bool {
+        self.weapons.iter().any(|weapon| weapon.kind() == kind)
+    }
+
+    pub(crate) fn add_weapon(&mut self, weapon: WeaponSlot) {
+        self.weapons.push(weapon);
+    }
+
+    /// Gain the character unique bonus for the new level
+    pub(crate) fn set_level(&mut self, level: u16) {
+        self.level = level;
+        self.update_stats();
+    }
+
+    pub(crate) fn passive_level(&self, item: PassiveItem) -> u8 {
+        self.passives.iter()
+            .find(|(owned, _)| *owned == item)
+            .map_or(0, |(_, level)| *level)
+    }
+
+    /// Recompute the final stats from the base stats, the character bonus and the passive items.
+    /// Gaining max HP also heals the player by the same amount.
+    fn update_stats(&mut self) {
+        let previous_max_hp = self.stats.max_hp;
+        let mut stats = self.base_stats;
+        self.level_bonus.apply(&mut stats, (self.level - 1) as f32);
+        for (item, level) in &self.passives {
+            item.apply(&mut stats, *level);
+        }
+        self.stats = stats;
+        self.character.hp += (self.stats.max_hp - previous_max_hp).max(0.);
+    }
+
+    /// Play the attack clip, the daggers being thrown on its throw event. Without one they are thrown right away.
+    pub(crate) fn attack(&mut self, direction: Vec2) {
+        let daggers_ready = self.weapons.iter()
+            .any(|weapon| matches!(weapon, WeaponSlot::Daggers(daggers) if daggers.is_ready()));
+        if !daggers_ready || self.pending_throw.is_some() {
+            return;
+        }
+        if self.character.animation().has_event(ATTACK, THROW_EVENT) && self.character.play(ATTACK) {
+            self.pending_throw = Some(direction);
+        } else {
+            self.throw_dagger(direction);
+        }
+    }
+
+    /// Throw daggers if the player has them
+    fn throw_dagger(&mut self, direction: Vec2) {
+        for weapon in self.weapons.iter_mut() {
+            if let WeaponSlot::Daggers(daggers) = weapon {
+                daggers.throw(self.character.world_position, direction, &self.stats);
+            }
+        }
     }
 }
 
@@ -80,4 +255,39 @@ impl Collidable for Player {
     fn hitbox(&self) -> Hitbox {
         self.character.hitbox()
     }
+
+    fn layer(&self) -> CollisionLayer {
+        CollisionLayer::Player
+    }
+}
+
+impl Damageable for Player {
+    /// Hits go through the resistances, the armor and the vulnerability. The ticks of the burns and poisons
+    /// aren't reduced by the armor and don't stagger the player. A hit never heals.
+    fn take_hit(&mut self, damage: f32, damage_type: DamageType, source: DamageSource) -> (f32, bool) {
+        let mut damage = self.stats.resistances.apply(damage, damage_type);
+        if source != DamageSource::Status {
+            damage = self.stats.damage_taken(damage);
+            self.character.flash();
+            // The hurt clip doesn't cut the attack, which throws the daggers
+            if self.pending_throw.is_none() {
+                self.character.play(HURT);
+            }
+        }
+        let damage = (damage * self.statuses.vulnerability()).max(0.);
+        self.character.hp -= damage;
+        (damage, self.character.hp <= 0.)
+    }
+
+    fn target(&self) -> DamageTarget {
+        DamageTarget::Player
+    }
+
+    fn hit_position(&self) -> Vec2 {
+        self.character.world_position
+    }
+
+    fn max_hp(&self) -> f32 {
+        self.stats.max_hp
+    }
 }
@@ -0,0 +1,164 @@
+use ::rand::{Rng, rngs::StdRng};
+use macroquad::prelude::*;
+
+use crate::{collision::{Collidable, CollisionLayer, Hitbox, OBB}, damage::{DamageSource, DamageTarget, DamageType, Damageable}, draw_utils::is_on_screen, entity::prop_definition::{PropDefinition, PropDefinitions, PropLook}};
+
+const HURT_COOLDOWN: f32 = 0.3; // Seconds during which a prop can't be hit again
+const SHAKE_DURATION: f32 = 0.15; // Seconds a prop shakes when hit
+const SHAKE_OFFSET: f32 = 2.; // Pixels a hit prop moves while shaking
+const PLACEMENT_ATTEMPTS: usize = 20; // Random positions tried for a generated prop before giving up on it
+const WOOD_COLOR: Color = Color::new(0.55, 0.35, 0.15, 1.);
+const DARK_WOOD_COLOR: Color = Color::new(0.35, 0.2, 0.08, 1.);
+const IRON_COLOR: Color = Color::new(0.3, 0.3, 0.35, 1.);
+const EMBER_COLOR: Color = Color::new(1., 0.45, 0.1, 1.);
+
+/// Object of the map that weapons can break
+pub(crate) struct Prop {
+    pub(crate) world_position: Vec2,
+    /// Index of the prop definition
+    pub(crate) kind: usize,
+    pub(crate) hp: f32,
+    max_hp: f32,
+    pub(crate) size: Vec2,
+    pub(crate) look: PropLook,
+    pub(crate) blocking: bool,
+    hurt_timer: f32,
+}
+
+impl Prop {
+    pub(crate) fn new(world_position: Vec2, kind: usize, definition: &PropDefinition) -> Self {
+        Self {
+            world_position,
+            kind,
+            hp: definition.hp,
+            max_hp: definition.hp,
+            size: definition.size,
+            look: definition.look,
+            blocking: definition.blocking,
+            hurt_timer: 0.,
+        }
+    }
+
+    pub(crate) fn update(&mut self) {
+        self.hurt_timer -= get_frame_time();
+    }
+
+    /// Props can't be hit again right after being hit, like ennemies
+    pub(crate) fn can_be_hit(&self) -> bool {
+        self.hurt_timer <= 0.
+    }
+
+    /// Lower part of the prop, which blocks the feet of the player
+    pub(crate) fn base(&self) -> Rect {
+        Rect::new(self.world_position.x - self.size.x / 2., self.world_position.y, self.size.x, self.size.y / 2.)
+    }
+
+    /// Vertical position of the bottom of the prop, ordering it with the characters
+    pub(crate) fn foot_y(&self) -> f32 {
+        self.world_position.y + self.size.y / 2.
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2) {
+        let mut screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        // Shake right after a hit
+        let shake_time = self.hurt_timer - (HURT_COOLDOWN - SHAKE_DURATION);
+        if shake_time > 0. {
+            screen_position.x += (shake_time * 100.).sin() * SHAKE_OFFSET;
+        }
+        let (width, height) = (self.size.x, self.size.y);
+        let (x, y) = (screen_position.x - width / 2., screen_position.y - height / 2.);
+        match self.look {
+            PropLook::Crate => {
+                draw_rectangle(x, y, width, height, WOOD_COLOR);
+                draw_rectangle_lines(x, y, width, height, 2., DARK_WOOD_COLOR);
+                draw_line(x, y, x + width, y + height, 2., DARK_WOOD_COLOR);
+                draw_line(x + width, y, x, y + height, 2., DARK_WOOD_COLOR);
+            }
+            PropLook::Brazier => {
+                // Bowl of embers on a stand
+                let bowl_height = height / 3.;
+                draw_rectangle(screen_position.x - 2., y + bowl_height, 4., height - bowl_height, IRON_COLOR);
+                draw_rectangle(x + width / 4., y + height - 3., width / 2., 3., IRON_COLOR);
+                draw_rectangle(x, y, width, bowl_height, IRON_COLOR);
+                draw_rectangle(x + 2., y, width - 4., 2., EMBER_COLOR);
+            }
+        }
+    }
+}
+
+impl Collidable for Prop {
+    fn hitbox(&self) -> Hitbox {
+        Hitbox::OBB(OBB {
+            world_center_position: self.world_position,
+            half: self.size / 2.,
+            rotation: 0.,
+        })
+    }
+
+    fn layer(&self) -> CollisionLayer {
+        CollisionLayer::Prop
+    }
+}
+
+impl Damageable for Prop {
+    /// Props don't resist anything, they break when out of HP
+    fn take_hit(&mut self, damage: f32, _damage_type: DamageType, _source: DamageSource) -> (f32, bool) {
+        self.hp -= damage;
+        self.hurt_timer = HURT_COOLDOWN;
+        (damage, self.hp <= 0.)
+    }
+
+    fn target(&self) -> DamageTarget {
+        DamageTarget::Prop
+    }
+
+    fn hit_position(&self) -> Vec2 {
+        self.world_position
+    }
+
+    fn max_hp(&self) -> f32 {
+        self.max_hp
+    }
+}
+
+/// Props of the map: the placed ones, then the generated ones scattered over the map at random,
+/// away from each other and from the start of the player
+pub(crate) fn generate_props(definitions: &PropDefinitions, map: Rect, player_start: Vec2, rng: &mut StdRng)
+    -> Vec<Prop> {
+    let mut props: Vec<Prop> = definitions.placed.iter()
+        .filter_map(|placed| {
+            let kind = definitions.kind_index(&placed.kind)?;
+            Some(Prop::new(placed.position, kind, &definitions.kinds[kind]))
+        })
+        .collect();
+
+    let generation = &definitions.generation;
+    let total_weight: f32 = definitions.kinds.iter().map(|kind| kind.weight).sum();
+    if total_weight <= 0. {
+        return props;
+    }
+    for _ in 0..generation.count {
+        let mut roll = rng.random_range(0.0..total_weight);
+        let kind = definitions.kinds.iter()
+            .position(|kind| {
+                roll -= kind.weight;
+                roll < 0.
+            })
+            .unwrap_or(definitions.kinds.len() - 1);
+        let position = (0..PLACEMENT_ATTEMPTS)
+            .map(|_| Vec2 {
+                x: rng.random_range(map.left()..map.right()),
+                y: rng.random_range(map.top()..map.bottom()),
+            })
+            .find(|position| position.distance(player_start) >= generation.safe_radius
+                && props.iter().all(|prop| prop.world_position.distance(*position) >= generation.min_spacing));
+        if let Some(position) = position {
+            props.push(Prop::new(position, kind, &definitions.kinds[kind]));
+        }
+    }
+    props
+}
+
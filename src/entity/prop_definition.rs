@@ -0,0 +1,101 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{data::{ensure, ensure_non_negative, ensure_positive, load_ron}, entity::pickup::{DropChance, validate_drops}};
+
+pub(crate) const PROPS_PATH: &str = "assets/props.ron";
+
+/// Props of the map, as described in `assets/props.ron`
+#[derive(Clone, Deserialize)]
+pub(crate) struct PropDefinitions {
+    pub(crate) kinds: Vec<PropDefinition>,
+    /// Props scattered at random over the map
+    pub(crate) generation: PropGeneration,
+    /// Props at fixed positions, on top of the generated ones
+    #[serde(default)]
+    pub(crate) placed: Vec<PlacedProp>,
+}
+
+/// A kind of prop
+#[derive(Clone, Deserialize)]
+pub(crate) struct PropDefinition {
+    pub(crate) name: String,
+    pub(crate) look: PropLook,
+    /// Size in pixels, the position of the prop being its center
+    pub(crate) size: Vec2,
+    pub(crate) hp: f32,
+    /// Whether the player walks around the prop instead of through it
+    #[serde(default)]
+    pub(crate) blocking: bool,
+    /// Particle effect of `assets/particles.ron` emitted when the prop breaks
+    pub(crate) break_effect: String,
+    /// Relative chance of the kind to be picked by the generation
+    #[serde(default = "default_weight")]
+    pub(crate) weight: f32,
+    #[serde(default)]
+    pub(crate) drops: Vec<DropChance>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum PropLook {
+    Crate,
+    /// Burns with the `brazier_fire` particle effect
+    Brazier,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) struct PropGeneration {
+    /// The same seed gives the same props, and the same drops when they are broken in the same order
+    pub(crate) seed: u64,
+    pub(crate) count: usize,
+    /// Minimum distance between two generated props
+    pub(crate) min_spacing: f32,
+    /// Radius around the start of the player kept free of generated props
+    pub(crate) safe_radius: f32,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct PlacedProp {
+    /// Name of the kind of prop
+    pub(crate) kind: String,
+    pub(crate) position: Vec2,
+}
+
+impl PropDefinitions {
+    /// Load the props, the error naming the faulty field when they are invalid
+    pub(crate) async fn load() -> Result<Self, String> {
+        let definitions: PropDefinitions = load_ron(PROPS_PATH).await?;
+        definitions.validate().map_err(|error| format!("Invalid {PROPS_PATH}: {error}"))?;
+        Ok(definitions)
+    }
+
+    pub(crate) fn kind_index(&self, name: &str) -> Option<usize> {
+        self.kinds.iter().position(|kind| kind.name == name)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for kind in &self.kinds {
+            let name = &kind.name;
+            ensure_positive(kind.size.x, &format!("{name}.size.x"))?;
+            ensure_positive(kind.size.y, &format!("{name}.size.y"))?;
+            ensure_positive(kind.hp, &format!("{name}.hp"))?;
+            ensure_non_negative(kind.weight, &format!("{name}.weight"))?;
+            validate_drops(&kind.drops, &format!("{name}.drops"))?;
+        }
+        let generation = &self.generation;
+        ensure_non_negative(generation.min_spacing, "generation.min_spacing")?;
+        ensure_non_negative(generation.safe_radius, "generation.safe_radius")?;
+        let total_weight: f32 = self.kinds.iter().map(|kind| kind.weight).sum();
+        ensure(generation.count == 0 || total_weight > 0., "generation.count",
+            "needs a kind of prop with a positive weight")?;
+        for (i, placed) in self.placed.iter().enumerate() {
+            ensure(self.kind_index(&placed.kind).is_some(), &format!("placed[{i}].kind"),
+                &format!("is not a kind of prop, got {}", placed.kind))?;
+        }
+        Ok(())
+    }
+}
+
+fn default_weight() -> f32 {
+    1.
+}
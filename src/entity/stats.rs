@@ -0,0 +1,121 @@
+use serde::Deserialize;
+
+use crate::damage::Resistances;
+
+/// Stats of the player, read by the weapons and the game instead of hardcoded values.
+/// Multipliers are 1.0 when they have no effect.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(default)]
+pub(crate) struct Stats {
+    pub(crate) max_hp: f32,
+    /// HP recovered each second
+    pub(crate) regen: f32,
+    /// Damage removed from each hit taken
+    pub(crate) armor: f32,
+    pub(crate) move_speed: f32,
+    /// Damage multiplier
+    pub(crate) might: f32,
+    /// Weapon size multiplier
+    pub(crate) area: f32,
+    pub(crate) projectile_speed: f32,
+    /// Fraction of the weapon cooldowns removed, from 0 to `MAX_COOLDOWN_REDUCTION`
+    pub(crate) cooldown_reduction: f32,
+    /// Lifetime multiplier of the projectiles and effects
+    pub(crate) duration: f32,
+    /// Extra projectiles thrown at once
+    pub(crate) amount: u8,
+    /// Multiplier of the chances to get rare drops
+    pub(crate) luck: f32,
+    /// Distance from which XP gems are attracted
+    pub(crate) magnet_radius: f32,
+    pub(crate) xp_gain: f32,
+    /// Chance for a weapon hit to burn the ennemy
+    pub(crate) burn_chance: f32,
+    /// Chance for a weapon hit to poison the ennemy
+    pub(crate) poison_chance: f32,
+    /// Fraction of the weapon damage dealt to the ennemies healed back
+    pub(crate) lifesteal: f32,
+    /// Fraction of the damage of each type ignored
+    pub(crate) resistances: Resistances,
+}
+
+pub(crate) const MAX_COOLDOWN_REDUCTION: f32 = 0.8;
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            max_hp: 10.,
+            regen: 0.,
+            armor: 0.,
+            move_speed: 1.,
+            might: 1.,
+            area: 1.,
+            projectile_speed: 1.,
+            cooldown_reduction: 0.,
+            duration: 1.,
+            amount: 0,
+            luck: 1.,
+            magnet_radius: 60.,
+            xp_gain: 1.,
+            burn_chance: 0.,
+            poison_chance: 0.,
+            lifesteal: 0.,
+            resistances: Resistances::default(),
+        }
+    }
+}
+
+impl Stats {
+    /// Damage actually taken from a hit once the armor is applied.
+    /// Armor can't cancel more than 90% of a hit.
+    pub(crate) fn damage_taken(&self, damage: f32) -> f32 {
+        (damage - self.armor).max(damage * 0.1)
+    }
+
+    /// Duration of a cooldown once the cooldown reduction is applied
+    pub(crate) fn cooldown(&self, base_cooldown: f32) -> f32 {
+        base_cooldown * (1. - self.cooldown_reduction.min(MAX_COOLDOWN_REDUCTION))
+    }
+}
+
+/// Flat bonus added to every stat, e.g. the bonus a character gains at each level
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct StatBonus {
+    pub(crate) max_hp: f32,
+    pub(crate) regen: f32,
+    pub(crate) armor: f32,
+    pub(crate) move_speed: f32,
+    pub(crate) might: f32,
+    pub(crate) area: f32,
+    pub(crate) projectile_speed: f32,
+    pub(crate) cooldown_reduction: f32,
+    pub(crate) duration: f32,
+    pub(crate) luck: f32,
+    pub(crate) magnet_radius: f32,
+    pub(crate) xp_gain: f32,
+    pub(crate) burn_chance: f32,
+    pub(crate) poison_chance: f32,
+    pub(crate) lifesteal: f32,
+}
+
+impl StatBonus {
+    /// Add the bonus `times` times to the stats
+    pub(crate) fn apply(&self, stats: &mut Stats, times: f32) {
+        stats.max_hp += self.max_hp * times;
+        stats.regen += self.regen * times;
+        stats.armor += self.armor * times;
+        stats.move_speed += self.move_speed * times;
+        stats.might += self.might * times;
+        stats.area += self.area * times;
+        stats.projectile_speed += self.projectile_speed * times;
+        stats.cooldown_reduction += self.cooldown_reduction * times;
+        stats.duration += self.duration * times;
+        stats.luck += self.luck * times;
+        stats.magnet_radius += self.magnet_radius * times;
+        stats.xp_gain += self.xp_gain * times;
+        stats.burn_chance += self.burn_chance * times;
+        stats.poison_chance += self.poison_chance * times;
+        stats.lifesteal += self.lifesteal * times;
+    }
+}
@@ -0,0 +1,351 @@
+use std::mem::discriminant;
+
+use ::rand::Rng;
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{damage::{DamageSource, DamageType, Hit}, data::{ensure, ensure_non_negative, ensure_positive}};
+
+const BURN_TICK: f32 = 0.5; // Seconds between two ticks of a burn
+const POISON_TICK: f32 = 1.; // Seconds between two ticks of a poison
+const MAX_POISON_STACKS: usize = 5;
+const KNOCKBACK_DECAY: f32 = 13.; // Rate per second at which the knockback speed fades out
+const KNOCKBACK_GRACE: f32 = 0.5; // Seconds during which a knocked back target resists the next knockbacks
+const KNOCKBACK_GRACE_RESISTANCE: f32 = 0.5;
+const INDICATOR_RADIUS: f32 = 3.;
+const INDICATOR_SPACING: f32 = 8.;
+
+/// Lasting effect of a hit, on the ennemies or the player
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum StatusEffect {
+    /// Damage each second, ticking every `BURN_TICK` seconds. Burns don't stack, the strongest one is kept.
+    Burn { damage: f32 },
+    /// Damage each second, ticking every `POISON_TICK` seconds. Up to `MAX_POISON_STACKS` poisons stack,
+    /// each with its own duration.
+    Poison { damage: f32 },
+    /// Fraction of the speed removed
+    Slow { slow: f32 },
+    /// Can't move nor attack
+    Freeze,
+    /// Can't move nor attack, for shorter than a freeze
+    Stun,
+    /// Fraction of extra damage taken
+    Vulnerability { damage: f32 },
+    /// Fraction of the knockback resisted
+    KnockbackResistance { resistance: f32 },
+}
+
+impl StatusEffect {
+    /// Strength compared when an effect that doesn't stack is applied again
+    fn strength(&self) -> f32 {
+        match *self {
+            StatusEffect::Burn { damage } | StatusEffect::Poison { damage }
+                | StatusEffect::Vulnerability { damage } => damage,
+            StatusEffect::Slow { slow } => slow,
+            StatusEffect::KnockbackResistance { resistance } => resistance,
+            StatusEffect::Freeze | StatusEffect::Stun => 0.,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            StatusEffect::Burn { .. } => ORANGE,
+            StatusEffect::Poison { .. } => LIME,
+            StatusEffect::Slow { .. } => SKYBLUE,
+            StatusEffect::Freeze => Color::new(0.7, 0.95, 1., 1.),
+            StatusEffect::Stun => YELLOW,
+            StatusEffect::Vulnerability { .. } => VIOLET,
+            StatusEffect::KnockbackResistance { .. } => GRAY,
+        }
+    }
+}
+
+/// Effect applied by a hit, e.g. by a weapon or an ennemy attack
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) struct StatusApplication {
+    pub(crate) effect: StatusEffect,
+    /// Seconds the effect lasts
+    pub(crate) duration: f32,
+    /// Chance for a hit to apply the effect
+    #[serde(default = "default_chance")]
+    pub(crate) chance: f32,
+}
+
+impl StatusApplication {
+    /// Roll whether a hit applies the effect
+    pub(crate) fn roll(&self, rng: &mut impl Rng) -> bool {
+        rng.random::<f32>() < self.chance
+    }
+}
+
+/// Effects applied to a character, counted down each frame, with its knockback
+#[derive(Default)]
+pub(crate) struct StatusEffects {
+    /// Effects with the seconds they have left
+    active: Vec<(StatusEffect, f32)>,
+    burn_timer: f32,
+    poison_timer: f32,
+    /// Speed in pixels per second at which the character is pushed
+    knockback: Vec2,
+}
+
+impl StatusEffects {
+    /// Apply an effect for `duration` seconds. Besides the poisons, an effect applied again lasts
+    /// for the longest of the two durations, keeping the strongest of the two.
+    pub(crate) fn apply(&mut self, effect: StatusEffect, duration: f32) {
+        if let StatusEffect::Poison { .. } = effect {
+            let stacks: Vec<usize> = (0..self.active.len())
+                .filter(|i| matches!(self.active[*i].0, StatusEffect::Poison { .. }))
+                .collect();
+            // A new stack replaces the one closest to running out
+            if stacks.len() >= MAX_POISON_STACKS && let Some(oldest) = stacks.into_iter()
+                .min_by(|a, b| self.active[*a].1.total_cmp(&self.active[*b].1)) {
+                self.active.swap_remove(oldest);
+            }
+            self.active.push((effect, duration));
+            return;
+        }
+        match self.active.iter_mut().find(|(active, _)| discriminant(active) == discriminant(&effect)) {
+            Some((active, timer)) => {
+                if effect.strength() >= active.strength() {
+                    *active = effect;
+                }
+                *timer = timer.max(duration);
+            }
+            None => self.active.push((effect, duration)),
+        }
+    }
+
+    /// Roll the effects of a hit and apply the ones landing, their durations multiplied by `duration`
+    pub(crate) fn apply_hit(&mut self, effects: &[StatusApplication], duration: f32, rng: &mut impl Rng) {
+        for application in effects {
+            if application.roll(rng) {
+                self.apply(application.effect, application.duration * duration);
+            }
+        }
+    }
+
+    /// Push the character away at `velocity` pixels per second, slowing down quickly.
+    /// The character resists the next knockbacks for a moment.
+    pub(crate) fn knock_back(&mut self, velocity: Vec2) {
+        if velocity == Vec2::ZERO {
+            return;
+        }
+        let resistance = self.active.iter()
+            .map(|(effect, _)| match effect {
+                StatusEffect::KnockbackResistance { resistance } => *resistance,
+                _ => 0.,
+            })
+            .fold(0., f32::max);
+        self.knockback += velocity * (1. - resistance.min(1.));
+        self.apply(StatusEffect::KnockbackResistance { resistance: KNOCKBACK_GRACE_RESISTANCE }, KNOCKBACK_GRACE);
+    }
+
+    /// Count the effects down by `dt` seconds. Returns the hits of the burn and poison ticks of the frame.
+    pub(crate) fn update(&mut self, dt: f32) -> Vec<Hit> {
+        let (mut burn, mut poison) = (0., 0.);
+        for (effect, _) in &self.active {
+            match effect {
+                StatusEffect::Burn { damage } => burn += damage,
+                StatusEffect::Poison { damage } => poison += damage,
+                _ => (),
+            }
+        }
+        let hits: Vec<Hit> = [(tick(&mut self.burn_timer, burn, BURN_TICK, dt), DamageType::Fire),
+            (tick(&mut self.poison_timer, poison, POISON_TICK, dt), DamageType::Poison)].into_iter()
+            .filter(|(damage, _)| *damage > 0.)
+            .map(|(damage, damage_type)| Hit::new(DamageSource::Status, damage, damage_type))
+            .collect();
+        for (_, timer) in self.active.iter_mut() {
+            *timer -= dt;
+        }
+        self.active.retain(|(_, timer)| *timer > 0.);
+        self.knockback *= (-KNOCKBACK_DECAY * dt).exp();
+        hits
+    }
+
+    /// The movement of the character for a frame of `dt` seconds, slowed down and knocked back
+    pub(crate) fn movement(&self, movement: Vec2, dt: f32) -> Vec2 {
+        if !self.can_act() {
+            return self.knockback * dt;
+        }
+        let slow = self.active.iter()
+            .map(|(effect, _)| match effect {
+                StatusEffect::Slow { slow } => *slow,
+                _ => 0.,
+            })
+            .fold(0., f32::max);
+        movement * (1. - slow.min(1.)) + self.knockback * dt
+    }
+
+    /// Frozen and stunned characters can't move nor attack
+    pub(crate) fn can_act(&self) -> bool {
+        !self.active.iter().any(|(effect, _)| matches!(effect, StatusEffect::Freeze | StatusEffect::Stun))
+    }
+
+    /// Multiplier of the damage taken
+    pub(crate) fn vulnerability(&self) -> f32 {
+        1. + self.active.iter()
+            .map(|(effect, _)| match effect {
+                StatusEffect::Vulnerability { damage } => *damage,
+                _ => 0.,
+            })
+            .fold(0., f32::max)
+    }
+
+    /// Draw a dot for each kind of effect in a row centered above `screen_position`
+    pub(crate) fn draw_indicators(&self, screen_position: Vec2) {
+        let mut kinds: Vec<&StatusEffect> = Vec::new();
+        for (effect, _) in &self.active {
+            // The knockback grace isn't worth showing
+            if !matches!(effect, StatusEffect::KnockbackResistance { .. })
+                && !kinds.iter().any(|kind| discriminant(*kind) == discriminant(effect)) {
+                kinds.push(effect);
+            }
+        }
+        let start = screen_position.x - (kinds.len() as f32 - 1.) * INDICATOR_SPACING / 2.;
+        for (i, effect) in kinds.iter().enumerate() {
+            draw_circle(start + i as f32 * INDICATOR_SPACING, screen_position.y, INDICATOR_RADIUS, effect.color());
+            draw_circle_lines(start + i as f32 * INDICATOR_SPACING, screen_position.y, INDICATOR_RADIUS, 1., BLACK);
+        }
+    }
+}
+
+/// Damage dealt during the frame by an effect dealing `damage` each second, ticking every `interval` seconds
+fn tick(timer: &mut f32, damage: f32, interval: f32, dt: f32) -> f32 {
+    if damage <= 0. {
+        *timer = 0.;
+        return 0.;
+    }
+    *timer += dt;
+    if *timer < interval {
+        return 0.;
+    }
+    *timer -= interval;
+    damage * interval
+}
+
+/// `field` names the list of effects in the errors
+pub(crate) fn validate_statuses(statuses: &[StatusApplication], field: &str) -> Result<(), String> {
+    for (i, status) in statuses.iter().enumerate() {
+        let field = format!("{field}[{i}]");
+        ensure_positive(status.duration, &format!("{field}.duration"))?;
+        ensure(status.chance >= 0. && status.chance <= 1., &format!("{field}.chance"),
+            &format!("must be between 0 and 1, got {}", status.chance))?;
+        let effect_field = format!("{field}.effect");
+        match status.effect {
+            StatusEffect::Burn { damage } | StatusEffect::Poison { damage } => ensure_positive(damage, &effect_field)?,
+            StatusEffect::Slow { slow } => ensure(slow > 0. && slow <= 1., &effect_field,
+                &format!("slow must be between 0 (excluded) and 1, got {slow}"))?,
+            StatusEffect::Vulnerability { damage } => ensure_non_negative(damage, &effect_field)?,
+            StatusEffect::KnockbackResistance { resistance } => ensure(resistance > 0. && resistance <= 1.,
+                &effect_field, &format!("resistance must be between 0 (excluded) and 1, got {resistance}"))?,
+            StatusEffect::Freeze | StatusEffect::Stun => (),
+        }
+    }
+    Ok(())
+}
+
+fn default_chance() -> f32 {
+    1.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1. / 60.;
+
+    fn poison_durations(statuses: &StatusEffects) -> Vec<f32> {
+        statuses.active.iter()
+            .filter(|(effect, _)| matches!(effect, StatusEffect::Poison { .. }))
+            .map(|(_, timer)| *timer)
+            .collect()
+    }
+
+    #[test]
+    fn new_poison_stack_replaces_the_one_closest_to_running_out() {
+        let mut statuses = StatusEffects::default();
+        for duration in [3., 1., 4., 5., 2.] {
+            statuses.apply(StatusEffect::Poison { damage: 1. }, duration);
+        }
+        statuses.apply(StatusEffect::Poison { damage: 1. }, 6.);
+        let mut durations = poison_durations(&statuses);
+        durations.sort_by(f32::total_cmp);
+        assert_eq!(durations, vec![2., 3., 4., 5., 6.]);
+    }
+
+    #[test]
+    fn burn_applied_again_keeps_the_strongest_and_the_longest() {
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusEffect::Burn { damage: 4. }, 1.);
+        statuses.apply(StatusEffect::Burn { damage: 2. }, 3.);
+        assert_eq!(statuses.active.len(), 1);
+        let (effect, timer) = statuses.active[0];
+        assert!(matches!(effect, StatusEffect::Burn { damage } if damage == 4.));
+        assert_eq!(timer, 3.);
+
+        statuses.apply(StatusEffect::Burn { damage: 6. }, 0.5);
+        let (effect, timer) = statuses.active[0];
+        assert!(matches!(effect, StatusEffect::Burn { damage } if damage == 6.));
+        assert_eq!(timer, 3.);
+    }
+
+    #[test]
+    fn tick_accumulates_the_frames_until_the_interval() {
+        let mut timer = 0.;
+        assert_eq!(tick(&mut timer, 2., 0.5, 0.2), 0.);
+        assert_eq!(tick(&mut timer, 2., 0.5, 0.2), 0.);
+        assert_eq!(tick(&mut timer, 2., 0.5, 0.2), 1.);
+        // The time left over counts towards the next tick
+        assert!((timer - 0.1).abs() < 1e-6);
+        assert_eq!(tick(&mut timer, 0., 0.5, 0.2), 0.);
+        assert_eq!(timer, 0.);
+    }
+
+    #[test]
+    fn burn_ticks_through_update() {
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusEffect::Burn { damage: 4. }, 2.);
+        let damage: f32 = (0..60)
+            .flat_map(|_| statuses.update(DT))
+            .map(|hit| hit.base_damage)
+            .sum();
+        assert!((damage - 4.).abs() < 1e-3, "{damage}");
+    }
+
+    #[test]
+    fn slow_reduces_the_movement() {
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusEffect::Slow { slow: 0.25 }, 1.);
+        statuses.apply(StatusEffect::Slow { slow: 0.5 }, 1.);
+        assert_eq!(statuses.movement(Vec2 { x: 2., y: 0. }, DT), Vec2 { x: 1., y: 0. });
+    }
+
+    #[test]
+    fn frozen_characters_only_move_with_the_knockback() {
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusEffect::Freeze, 1.);
+        assert!(!statuses.can_act());
+        assert_eq!(statuses.movement(Vec2 { x: 2., y: 0. }, DT), Vec2::ZERO);
+
+        statuses.knock_back(Vec2 { x: 0., y: 120. });
+        assert_eq!(statuses.movement(Vec2 { x: 2., y: 0. }, 0.5), Vec2 { x: 0., y: 60. });
+    }
+
+    #[test]
+    fn knockback_travels_the_same_distance_whatever_the_frame_rate() {
+        let distance = |dt: f32| {
+            let mut statuses = StatusEffects::default();
+            statuses.knock_back(Vec2 { x: 100., y: 0. });
+            let mut distance = 0.;
+            for _ in 0..(1. / dt) as usize {
+                distance += statuses.movement(Vec2::ZERO, dt).x;
+                statuses.update(dt);
+            }
+            distance
+        };
+        let (at_30, at_144) = (distance(1. / 30.), distance(1. / 144.));
+        assert!((at_30 - at_144).abs() / at_144 < 0.25, "{at_30} {at_144}");
+    }
+}
@@ -0,0 +1,59 @@
+use macroquad::prelude::*;
+
+use crate::draw_utils::is_on_screen;
+
+const GEM_SPEED: f32 = 240.; // Pixels per second towards the player once attracted
+const GEM_RADIUS: f32 = 5.;
+const PICKUP_DISTANCE: f32 = 12.; // Distance from the player at which a gem is collected
+pub(crate) const SMALL_GEM_XP: u32 = 1;
+pub(crate) const BIG_GEM_XP: u32 = 5;
+
+/// Experience dropped by killed ennemies
+pub(crate) struct XpGem {
+    pub(crate) world_position: Vec2,
+    pub(crate) xp: u32,
+    /// Once attracted, a gem flies to the player until it is collected
+    attracted: bool,
+}
+
+impl XpGem {
+    pub(crate) fn new(world_position: Vec2, xp: u32) -> Self {
+        Self {
+            world_position,
+            xp,
+            attracted: false,
+        }
+    }
+
+    /// Make the gem fly to the player wherever it is
+    pub(crate) fn attract(&mut self) {
+        self.attracted = true;
+    }
+
+    /// Move the gem towards the player when it is in the magnet radius.
+    /// Returns true when the gem reaches the player.
+    pub(crate) fn update(&mut self, player_position: Vec2, magnet_radius: f32) -> bool {
+        let to_player = player_position - self.world_position;
+        let distance = to_player.length();
+        if distance < PICKUP_DISTANCE {
+            return true;
+        }
+        if distance < magnet_radius {
+            self.attracted = true;
+        }
+        if self.attracted {
+            self.world_position += to_player / distance * (GEM_SPEED * get_frame_time()).min(distance);
+        }
+        false
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2) {
+        let screen_position = self.world_position - screen_origin_position;
+        if !is_on_screen(screen_position) {
+            return;
+        }
+        let color = if self.xp >= BIG_GEM_XP { RED } else { SKYBLUE };
+        draw_poly(screen_position.x, screen_position.y, 4, GEM_RADIUS, 0., color);
+        draw_poly_lines(screen_position.x, screen_position.y, 4, GEM_RADIUS, 0., 1., WHITE);
+    }
+}
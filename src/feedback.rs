@@ -0,0 +1,175 @@
+use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams};
+use macroquad::prelude::*;
+
+use crate::damage::{DamageEvent, DamageType};
+use crate::settings::Settings;
+
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.8; // Seconds before a damage number has faded out
+const DAMAGE_NUMBER_RISE_SPEED: f32 = 40.; // Pixels per second
+const DAMAGE_NUMBER_OFFSET: f32 = 30.; // Height above the hit position where the number appears
+const DAMAGE_NUMBER_SIZE: f32 = 20.;
+const CRIT_NUMBER_SIZE: f32 = 28.;
+const CRIT_COLOR: Color = Color::new(1., 0.8, 0.1, 1.);
+const PLAYER_DAMAGE_COLOR: Color = Color::new(1., 0.3, 0.3, 1.);
+const TICK_NUMBER_SIZE: f32 = 16.; // Damage of the burn and poison ticks
+const HIT_STOP_DURATION: f32 = 0.06; // Seconds during which the game freezes on a big hit
+const HIT_STOP_MIN_DAMAGE: f32 = 5.; // Damage from which a hit is big, crits always are
+const SLOW_MOTION_DURATION: f32 = 0.5; // Real seconds of slow motion after a big kill
+const SLOW_MOTION_SCALE: f32 = 0.3; // Speed of the game during the slow motion
+const SLOW_MOTION_MIN_HP: f32 = 8.; // Max HP from which killing an ennemy slows the game down
+
+const FLASH_VERTEX_SHADER: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying lowp vec2 uv;
+varying lowp vec4 color;
+uniform mat4 Model;
+uniform mat4 Projection;
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}
+"#;
+
+/// Keeps the shape of the sprite but paints it white
+const FLASH_FRAGMENT_SHADER: &str = r#"#version 100
+varying lowp vec2 uv;
+varying lowp vec4 color;
+uniform sampler2D Texture;
+void main() {
+    gl_FragColor = vec4(color.rgb, texture2D(Texture, uv).a * color.a);
+}
+"#;
+
+struct DamageNumber {
+    world_position: Vec2,
+    text: String,
+    color: Color,
+    size: f32,
+    timer: f32,
+}
+
+/// Feedback of the hits: floating damage numbers, the white flash of struck characters,
+/// the hit-stop freezing the game for a moment on big hits and the slow motion on the killing
+/// blow of big ennemies. Each one can be turned off in the settings.
+pub(crate) struct Feedback {
+    damage_numbers: Vec<DamageNumber>,
+    hit_stop_timer: f32,
+    slow_motion_timer: f32,
+    /// Fraction of a game update due during the slow motion, an update being run once it reaches 1
+    time_accumulator: f32,
+    flash_material: Material,
+}
+
+impl Feedback {
+    pub(crate) fn new() -> Self {
+        Self {
+            damage_numbers: Vec::new(),
+            hit_stop_timer: 0.,
+            slow_motion_timer: 0.,
+            time_accumulator: 0.,
+            flash_material: flash_material(),
+        }
+    }
+
+    /// React to a hit of the frame
+    pub(crate) fn on_damage(&mut self, event: &DamageEvent, settings: &Settings) {
+        if settings.damage_numbers {
+            let (color, size) = match (event.to_player(), event.crit) {
+                (true, _) => (PLAYER_DAMAGE_COLOR, DAMAGE_NUMBER_SIZE),
+                _ if event.is_tick() => (damage_type_color(event.damage_type), TICK_NUMBER_SIZE),
+                (false, true) => (CRIT_COLOR, CRIT_NUMBER_SIZE),
+                (false, false) => (damage_type_color(event.damage_type), DAMAGE_NUMBER_SIZE),
+            };
+            self.damage_numbers.push(DamageNumber {
+                world_position: event.world_position - Vec2 { x: 0., y: DAMAGE_NUMBER_OFFSET },
+                text: format!("{:.0}", event.damage.ceil()),
+                color,
+                size,
+                timer: 0.,
+            });
+        }
+        if settings.hit_stop && !event.to_player() && !event.is_tick() && (event.crit || event.damage >= HIT_STOP_MIN_DAMAGE) {
+            self.hit_stop_timer = HIT_STOP_DURATION;
+        }
+        if !settings.reduced_motion && event.kill && event.target_max_hp >= SLOW_MOTION_MIN_HP {
+            self.slow_motion_timer = SLOW_MOTION_DURATION;
+        }
+    }
+
+    /// Whether the game is updated this frame: it isn't during a hit-stop, and only some frames are
+    /// during the slow motion. Movements being done per update, skipping updates slows everything down.
+    pub(crate) fn should_update(&mut self) -> bool {
+        let dt = get_frame_time();
+        if self.hit_stop_timer > 0. {
+            self.hit_stop_timer -= dt;
+            return false;
+        }
+        if self.slow_motion_timer > 0. {
+            self.slow_motion_timer -= dt;
+            self.time_accumulator += SLOW_MOTION_SCALE;
+            if self.time_accumulator < 1. {
+                return false;
+            }
+            self.time_accumulator -= 1.;
+        }
+        true
+    }
+
+    pub(crate) fn update(&mut self) {
+        let dt = get_frame_time();
+        for number in self.damage_numbers.iter_mut() {
+            number.timer += dt;
+            number.world_position.y -= DAMAGE_NUMBER_RISE_SPEED * dt;
+        }
+        self.damage_numbers.retain(|number| number.timer < DAMAGE_NUMBER_LIFETIME);
+    }
+
+    /// Material drawing struck characters in white, `None` when the hit flash is turned off
+    pub(crate) fn flash_material(&self, settings: &Settings) -> Option<&Material> {
+        settings.hit_flash.then_some(&self.flash_material)
+    }
+
+    /// Material drawing the shape of a sprite in the plain color it is drawn with, e.g. for outlines
+    pub(crate) fn silhouette_material(&self) -> &Material {
+        &self.flash_material
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2) {
+        for number in &self.damage_numbers {
+            let screen_position = number.world_position - screen_origin_position;
+            let size = measure_text(&number.text, None, number.size as u16, 1.);
+            let alpha = 1. - number.timer / DAMAGE_NUMBER_LIFETIME;
+            draw_text(&number.text, screen_position.x - size.width / 2., screen_position.y, number.size,
+                number.color.with_alpha(alpha));
+        }
+    }
+}
+
+/// Color of the numbers of the damage dealt to the ennemies, telling the damage types apart
+fn damage_type_color(damage_type: DamageType) -> Color {
+    match damage_type {
+        DamageType::Physical => WHITE,
+        DamageType::Fire => ORANGE,
+        DamageType::Ice => Color::new(0.6, 0.85, 1., 1.),
+        DamageType::Lightning => Color::new(1., 1., 0.5, 1.),
+        DamageType::Poison => Color::new(0.5, 0.9, 0.3, 1.),
+    }
+}
+
+fn flash_material() -> Material {
+    let pipeline_params = PipelineParams {
+        color_blend: Some(BlendState::new(
+            Equation::Add,
+            BlendFactor::Value(BlendValue::SourceAlpha),
+            BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+        )),
+        ..Default::default()
+    };
+    let shaders = ShaderSource::Glsl { vertex: FLASH_VERTEX_SHADER, fragment: FLASH_FRAGMENT_SHADER };
+    // The shaders are fixed, failing to compile them is a bug
+    load_material(shaders, MaterialParams { pipeline_params, ..Default::default() })
+        .expect("Failed to compile the hit flash shaders")
+}
@@ -1,32 +1,124 @@
+use std::f32::consts::TAU;
+
+use ::rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
 use macroquad::prelude::*;
 use rand_distr::Distribution;
 
-use crate::collision::{Collidable, hitbox_intersects};
+use crate::asset_manager::{AssetManager, FontHandle, SpriteHandle, TextureHandle};
+use crate::camera::GameCamera;
+use crate::collision::collides;
+use crate::data::Definitions;
+use crate::damage::{DamageEvent, DamageSource, DamageStats, DamageTarget, DamageType, Hit, deal_damage};
+use crate::entity::boss::BossAction;
 use crate::entity::character::Direction;
+use crate::entity::chest::Chest;
+use crate::entity::elite::EliteDefinitions;
 use crate::entity::ennemy::Ennemy;
+use crate::entity::ennemy_definition::EnnemyDefinition;
+use crate::entity::ennemy_projectile::{EnnemyProjectile, MARKER_COLOR, ProjectileKind};
+use crate::entity::pickup::{Pickup, PickupKind, add_pickup, roll_drops};
 use crate::entity::player::Player;
+use crate::entity::prop::{Prop, generate_props};
+use crate::entity::prop_definition::{PropDefinitions, PropLook};
+use crate::entity::xp_gem::{BIG_GEM_XP, SMALL_GEM_XP, XpGem};
+use crate::feedback::Feedback;
+use crate::input::action::InputAction;
+use crate::input::input_map::InputMap;
+use crate::particles::particle_system::ParticleSystem;
+use crate::progression::{Progression, Upgrade};
+use crate::render_queue::{Layer, RenderQueue};
+use crate::settings::Settings;
 use crate::survivor_rng::SurvivorRng;
+use crate::survivor_ui::hud::{BossBar, Hud, HudInfo};
+use crate::survivor_ui::minimap::{MinimapInfo, draw_minimap};
+use crate::telegraph::TelegraphShape;
+use crate::weapons::aura::Aura;
 use crate::weapons::dagger::DaggerAggregate;
 use crate::weapons::sword::Sword;
+use crate::weapons::weapon_definition::WeaponDefinitions;
+use crate::weapons::weapon_slot::{WeaponKind, WeaponSlot};
+
+pub(crate) const GRASS_TEXTURE_PATH: &str = "assets/grass_zoom.png";
 
 const MOVE_DISTANCE: f32 = 1.;
 const PLAYER_RADIUS: f32 = 10.;
 const MAX_ENNEMIES_NB: u8 = 10;
-const ENNEMY_SPEED: f32 = 0.1;
 
-const MAP_WIDTH: f32 = 2048.;
-const MAP_HEIGHT: f32 = 2048.;
+pub(crate) const MAP_WIDTH: f32 = 2048.;
+pub(crate) const MAP_HEIGHT: f32 = 2048.;
+
+const BIG_GEM_CHANCE: f32 = 0.05; // Chance for a killed ennemy to drop a big gem, before luck
+const MAX_PICKUPS: usize = 100; // Pickups lying on the ground at once, gold is merged past it
+const PICKUP_SCATTER: f32 = 10.; // Distance from the dead ennemy at which its pickups can drop
+const BOMB_DAMAGE: f32 = 100.; // Damage dealt by a bomb to every ennemy on the screen
+const FREEZE_DURATION: f32 = 5.; // Seconds during which the ennemies stand still after a freeze pickup
+const FREEZE_COLOR: Color = Color::new(0.6, 0.85, 1., 0.15); // Tint of the screen while the ennemies are frozen
+const CHEST_FIVE_REWARDS_CHANCE: f32 = 0.05; // Chance for a chest to give 5 upgrades, before luck
+const CHEST_THREE_REWARDS_CHANCE: f32 = 0.25; // Chance for a chest to give at least 3 upgrades, before luck
+const BOSS_SPAWN_DISTANCE: f32 = 400.; // Distance from the player at which the bosses spawn, out of the screen
+const BOSS_SPAWN_TRIES: usize = 8; // Random directions tried for a boss to spawn inside the map
+const SPLIT_DISTANCE: f32 = 12.; // Distance from a killed splitting elite at which the ennemies split from it appear
+
+// Particle effects of `assets/particles.ron` used by the game
+const HIT_PARTICLES: &str = "hit_sparks";
+const PLAYER_HIT_PARTICLES: &str = "blood";
+const DEATH_PARTICLES: &str = "death_poof";
+const GEM_PARTICLES: &str = "gem_sparkle";
+const AURA_PARTICLES: &str = "aura_motes";
+const DAGGER_PARTICLES: &str = "dagger_trail";
+const CHEST_PARTICLES: &str = "chest_burst";
+const BRAZIER_PARTICLES: &str = "brazier_fire";
+const SLAM_PARTICLES: &str = "boss_slam";
+const BOSS_PHASE_PARTICLES: &str = "boss_phase";
+const BOMB_PARTICLES: &str = "bomb_blast";
+const GEM_SPARKLE_RADIUS: f32 = 6.;
 
 
 pub struct Game {
     player: Player,
     ennemies: Vec<Ennemy>,
+    ennemy_projectiles: Vec<EnnemyProjectile>,
+    /// Time of the next spawn of each kind of ennemy defined as a boss, infinite for the other kinds
+    boss_spawns: Vec<f32>,
+    xp_gems: Vec<XpGem>,
+    chests: Vec<Chest>,
+    pickups: Vec<Pickup>,
+    props: Vec<Prop>,
+    /// Seeded by the props definitions, so the props and their drops are the same from one run to the other
+    prop_rng: StdRng,
+    /// Seconds left during which the ennemies stand still
+    freeze_timer: f32,
     score: i16,
+    gold: u32,
+    /// Seconds survived, not counting the pauses, hit-stops and slowed down parts
+    time: f32,
+    progression: Progression,
+    last_upgrade: Option<Upgrade>,
     rng: SurvivorRng,
-    player_idle_texture: Texture2D,
-    player_walking_texture: Texture2D,
-    orc_texture: Texture2D,
-    grass_texture: Texture2D,
+    weapon_definitions: WeaponDefinitions,
+    ennemy_definitions: Vec<EnnemyDefinition>,
+    elite_definitions: EliteDefinitions,
+    prop_definitions: PropDefinitions,
+    textures: GameTextures,
+    feedback: Feedback,
+    particles: ParticleSystem,
+    camera: GameCamera,
+    damage_stats: DamageStats,
+}
+
+/// Textures used during a run
+pub(crate) struct GameTextures {
+    pub(crate) sword: SpriteHandle,
+    pub(crate) dagger: SpriteHandle,
+    /// Sprite sheets of the player clips, in the order of the clips of its animation
+    pub(crate) player: Vec<SpriteHandle>,
+    /// Sprite sheets of the ennemies clips, in the order of their definitions
+    pub(crate) ennemies: Vec<Vec<SpriteHandle>>,
+    /// Sprites of the particle effects drawing one, in the order of their definitions
+    pub(crate) particles: Vec<Option<SpriteHandle>>,
+    /// The background is too large for the atlas
+    pub(crate) grass: TextureHandle,
+    pub(crate) font: FontHandle,
 }
 
 pub struct GameData {
@@ -36,24 +128,19 @@ pub struct GameData {
 
 
 impl Game {
-    pub(crate) fn new(sword_texture: &Texture2D, player_idle_texture: &Texture2D, 
-        player_walking_texture: &Texture2D, dagger_texture: &Texture2D, orc_texture: &Texture2D, grass_texture: &Texture2D) -> Self {
-        let sword = Sword::new(
-            vec2(screen_width() / 2.0, screen_height() / 2.),
-            0.,
-            0.2,
-            sword_texture.clone()
+    pub(crate) fn new(definitions: &Definitions, character: usize, textures: GameTextures, settings: &Settings) -> Self {
+        let character = &definitions.roster[character];
+        let player_position = Vec2::new(
+            screen_width() / 2.0, 
+            screen_height() / 2.0
         );
-            
-        let daggers = DaggerAggregate::new(dagger_texture.clone());
+        let starting_weapon = new_weapon(character.starting_weapon, player_position, &definitions.weapons, &textures);
         
         let player = Player::new(
-            Vec2::new(
-                screen_width() / 2.0, 
-                screen_height() / 2.0
-            ), 
-            sword,
-            daggers,
+            player_position, 
+            character,
+            starting_weapon,
+            textures.player.clone(),
         );
         
         
@@ -61,6 +148,9 @@ impl Game {
         
         let score: i16 = 0;
         
+        let mut prop_rng = StdRng::seed_from_u64(definitions.props.generation.seed);
+        let props = generate_props(&definitions.props, map_rect(), player_position, &mut prop_rng);
+        
         let rng = SurvivorRng::new(
             PLAYER_RADIUS, 
             screen_width() - PLAYER_RADIUS, 
@@ -71,140 +161,852 @@ impl Game {
         Game {
             player,
             ennemies,
+            ennemy_projectiles: Vec::new(),
+            boss_spawns: boss_spawn_times(&definitions.ennemies, 0.),
+            xp_gems: Vec::new(),
+            chests: Vec::new(),
+            pickups: Vec::new(),
+            props,
+            prop_rng,
+            freeze_timer: 0.,
             score,
+            gold: 0,
+            time: 0.,
+            progression: Progression::new(),
+            last_upgrade: None,
             rng,
-            player_idle_texture: player_idle_texture.clone(),
-            player_walking_texture: player_walking_texture.clone(),
-            orc_texture: orc_texture.clone(),
-            grass_texture: grass_texture.clone(),
+            weapon_definitions: definitions.weapons.clone(),
+            ennemy_definitions: definitions.ennemies.clone(),
+            elite_definitions: definitions.elites.clone(),
+            prop_definitions: definitions.props.clone(),
+            particles: ParticleSystem::new(&definitions.particles, textures.particles.clone(), settings.particle_budget),
+            textures,
+            feedback: Feedback::new(),
+            camera: GameCamera::new(),
+            damage_stats: DamageStats::default(),
         }
     }
         
-    pub(crate) fn update(&mut self) -> GameData {
-        self.get_input();
-        self.player.udpate();
-        self.manage_collisions();
-        self.populate_ennemies();
-        self.draw();
+    pub(crate) fn update(&mut self, input_map: &InputMap, settings: &Settings, assets: &AssetManager) -> GameData {
+        // The game stands still during a hit-stop and runs slower in slow motion, but is still drawn
+        if self.feedback.should_update() {
+            self.time += get_frame_time();
+            self.freeze_timer -= get_frame_time();
+            self.get_input(input_map);
+            let targets: Vec<Vec2> = self.ennemies.iter()
+                .filter(|ennemy| ennemy.is_alive())
+                .map(|ennemy| ennemy.character.world_position)
+                .collect();
+            self.player.udpate(&targets);
+            let mut damage_events = self.manage_collisions();
+            for hit in self.player.statuses.update(get_frame_time()) {
+                damage_events.push(deal_damage(hit, &mut self.player, &mut self.rng.rng));
+            }
+            damage_events.extend(self.update_ennemy_projectiles());
+            damage_events.extend(self.hit_props());
+            damage_events.extend(self.collect_pickups());
+            for event in damage_events {
+                self.damage_stats.record(&event);
+                self.on_damage(&event);
+                self.feedback.on_damage(&event, settings);
+                self.camera.on_damage(&event);
+                self.emit_hit_particles(&event);
+            }
+            self.feedback.update();
+            self.emit_particles();
+            self.particles.update(settings.particle_budget);
+            self.collect_xp_gems();
+            self.open_chests();
+            self.populate_ennemies();
+            self.spawn_bosses();
+        }
+        self.camera.update();
+        self.draw(settings, assets);
         
         GameData {
-            is_game_over: self.player.character.hp <= 0,
+            is_game_over: self.player.character.hp <= 0.,
             score: self.score,
         }
     }
         
-    fn manage_collisions(&mut self) {
+    /// Use reloaded definitions and textures for the rest of the run
+    pub(crate) fn apply_definitions(&mut self, definitions: &Definitions, character: usize, textures: GameTextures) {
+        let weapon_definitions = &definitions.weapons;
+        let ennemy_definitions = &definitions.ennemies;
+        self.player.apply_definition(&definitions.roster[character], textures.player.clone());
+        for weapon in self.player.weapons.iter_mut() {
+            weapon.apply_definition(weapon_definitions, &textures);
+        }
+        // Ennemies whose definition was removed disappear
+        self.ennemies.retain(|ennemy| ennemy.kind < ennemy_definitions.len());
+        for ennemy in self.ennemies.iter_mut() {
+            ennemy.apply_definition(&ennemy_definitions[ennemy.kind]);
+        }
+        self.weapon_definitions = weapon_definitions.clone();
+        self.ennemy_definitions = ennemy_definitions.clone();
+        self.boss_spawns = boss_spawn_times(ennemy_definitions, self.time);
+        self.elite_definitions = definitions.elites.clone();
+        // The props are placed again from the start of the new seed
+        self.prop_rng = StdRng::seed_from_u64(definitions.props.generation.seed);
+        self.props = generate_props(&definitions.props, map_rect(), self.player.character.world_position,
+            &mut self.prop_rng);
+        self.prop_definitions = definitions.props.clone();
+        self.particles.apply_definitions(&definitions.particles, textures.particles.clone());
+        self.textures = textures;
+    }
+        
+    /// Returns the hits of the frame
+    fn manage_collisions(&mut self) -> Vec<DamageEvent> {
+        let mut damage_events = Vec::new();
+        let mut boss_actions = Vec::new();
+        let mut splits = Vec::new();
         // Moving ennemies + checking ennemies - player collision
         for ennemy in self.ennemies.iter_mut() {
-            let direction = get_direction_from_vector(ennemy.vel);
-            ennemy.move_by(ennemy.vel * ENNEMY_SPEED, direction);
+            if !ennemy.is_alive() {
+                if ennemy.update_death() {
+                    let big_gem_chance = BIG_GEM_CHANCE * self.player.stats.luck;
+                    // Elites always drop a big gem
+                    let big_gem = ennemy.is_elite() || self.rng.rng.random::<f32>() < big_gem_chance;
+                    let xp = if big_gem { BIG_GEM_XP } else { SMALL_GEM_XP };
+                    self.xp_gems.push(XpGem::new(ennemy.character.world_position, xp));
+                    let definition = &self.ennemy_definitions[ennemy.kind];
+                    let elite = &self.elite_definitions;
+                    let chest_chance = definition.chest_chance + if ennemy.is_elite() { elite.chest_chance } else { 0. };
+                    if definition.boss.is_some() || self.rng.rng.random::<f32>() < chest_chance {
+                        self.chests.push(Chest::new(ennemy.character.world_position));
+                    }
+                    let mut drops = roll_drops(&definition.drops, self.player.stats.luck, &mut self.rng.rng);
+                    if ennemy.is_elite() {
+                        drops.extend(roll_drops(&elite.drops, self.player.stats.luck, &mut self.rng.rng));
+                    }
+                    for kind in drops {
+                        let offset = Vec2 {
+                            x: self.rng.rng.random_range(-PICKUP_SCATTER..PICKUP_SCATTER),
+                            y: self.rng.rng.random_range(-PICKUP_SCATTER..PICKUP_SCATTER),
+                        };
+                        let pickup = Pickup::new(ennemy.character.world_position + offset, kind);
+                        add_pickup(&mut self.pickups, pickup, MAX_PICKUPS);
+                    }
+                }
+                continue;
+            }
+            for hit in ennemy.statuses.update(get_frame_time()) {
+                if ennemy.is_alive() {
+                    let event = deal_damage(hit, ennemy, &mut self.rng.rng);
+                    if event.kill {
+                        splits.extend(ennemy.split().map(|split| (ennemy.kind, ennemy.character.world_position, split)));
+                    }
+                    damage_events.push(event);
+                }
+            }
+            if !ennemy.is_alive() {
+                continue;
+            }
+            ennemy.update(get_frame_time());
+            // Frozen and stunned ennemies can still be hit, but don't move nor hurt the player
+            let frozen = self.freeze_timer > 0. || !ennemy.statuses.can_act();
+            let definition = &self.ennemy_definitions[ennemy.kind];
+            let boss = &definition.boss;
+            if frozen {
+                ennemy.update_frozen();
+            } else if let Some(boss) = boss && let Some(brain) = &mut ennemy.boss {
+                let hp_ratio = ennemy.character.hp / ennemy.max_hp;
+                let (movement, actions) = brain.update(boss, ennemy.character.world_position,
+                    self.player.character.world_position, hp_ratio, ennemy.vel * ennemy.speed);
+                ennemy.move_by(movement, get_direction_from_vector(movement));
+                boss_actions.extend(actions);
+            } else if let Some(ranged) = &definition.ranged {
+                let (movement, shot) = ennemy.update_ranged(ranged, self.player.character.world_position,
+                    ennemy.vel * ennemy.speed);
+                ennemy.move_by(movement, get_direction_from_vector(movement));
+                if let Some(target) = shot {
+                    self.ennemy_projectiles.push(EnnemyProjectile::new(ennemy.character.world_position, target,
+                        ranged.projectile, ranged.damage, ranged.damage_type, ranged.effects.clone()));
+                }
+            } else {
+                let direction = get_direction_from_vector(ennemy.vel);
+                ennemy.move_by(ennemy.vel * ennemy.speed, direction);
+            }
             
-            if hitbox_intersects(&ennemy.hitbox(), &self.player.hitbox()) {
-                self.player.character.hp -= 1;
-                ennemy.collided = true;
+            // Ennemies go through the player while they dash
+            if !frozen && !self.player.is_invulnerable() && collides(ennemy, &self.player) {
+                // Bosses aren't spent by the hit, they hurt the player again after a while
+                let rng = &mut self.rng.rng;
+                let (event, spent) = if let Some(boss) = boss && let Some(brain) = &mut ennemy.boss {
+                    (brain.touch(boss, ennemy.damage).map(|damage| {
+                        deal_damage(Hit::new(DamageSource::Ennemy, damage, DamageType::Physical), &mut self.player, rng)
+                    }), false)
+                } else if let Some(lifesteal) = ennemy.lifesteal() {
+                    // Vampiric elites latch on the player, feeding on it instead of being spent
+                    let event = ennemy.latch().then(|| {
+                        deal_damage(Hit::new(DamageSource::Ennemy, ennemy.damage, DamageType::Physical), &mut self.player, rng)
+                    });
+                    if let Some(event) = &event {
+                        ennemy.heal(event.damage * lifesteal);
+                    }
+                    (event, false)
+                } else {
+                    let hit = Hit::new(DamageSource::Ennemy, ennemy.damage, DamageType::Physical);
+                    (Some(deal_damage(hit, &mut self.player, rng)), true)
+                };
+                if let Some(event) = event {
+                    let away = (self.player.character.world_position - ennemy.character.world_position).normalize_or_zero();
+                    self.player.statuses.knock_back(away * definition.knockback);
+                    self.player.statuses.apply_hit(&definition.on_hit, 1., &mut self.rng.rng);
+                    damage_events.push(event);
+                }
+                if spent {
+                    // The ennemy is spent by the hit, without dropping anything
+                    ennemy.die(false);
+                    self.particles.emit(DEATH_PARTICLES, ennemy.character.world_position, Vec2::ZERO, 0.);
+                    continue;
+                }
+            }
+            if !ennemy.can_be_hit() {
+                continue;
             }
-            if self.player.weapons_collides_with(&ennemy.hitbox()) {
-                self.score += 1;
-                ennemy.collided = true;
+            // Each weapon touching the ennemy hits it on its own, until it dies
+            for slot in 0..self.player.weapons.len() {
+                if !ennemy.is_alive() {
+                    break;
+                }
+                let Some(hit) = self.player.weapon_hit(slot, ennemy) else {
+                    continue;
+                };
+                let stats = &self.player.stats;
+                let event = deal_damage(Hit::weapon(hit.kind, hit.damage, hit.damage_type, stats.luck), ennemy,
+                    &mut self.rng.rng);
+                if event.kill {
+                    splits.extend(ennemy.split().map(|split| (ennemy.kind, ennemy.character.world_position, split)));
+                } else {
+                    ennemy.statuses.apply_hit(&hit.effects, stats.duration, &mut self.rng.rng);
+                    // Bosses stand their ground
+                    if ennemy.boss.is_none() {
+                        let away = (ennemy.character.world_position - self.player.character.world_position)
+                            .normalize_or_zero();
+                        ennemy.statuses.knock_back(away * hit.knockback);
+                    }
+                }
+                damage_events.push(event);
             }
         }
         
-        self.ennemies.retain(|ennemy| !ennemy.collided);
+        self.ennemies.retain(|ennemy| !ennemy.is_gone());
+        self.spawn_splits(splits);
+        damage_events.extend(self.resolve_boss_actions(boss_actions));
+        damage_events
     }
+    
+    /// Land the attacks of the bosses. Returns the hits of the slams.
+    fn resolve_boss_actions(&mut self, actions: Vec<BossAction>) -> Vec<DamageEvent> {
+        let mut damage_events = Vec::new();
+        for action in actions {
+            match action {
+                BossAction::Strike { origin, shape, damage } => {
+                    // Dust over the area hit
+                    let (center, radius) = match shape {
+                        TelegraphShape::Cone { direction, radius, .. } => (origin + direction * radius / 2., radius / 2.),
+                        TelegraphShape::Circle { radius } => (origin, radius),
+                        TelegraphShape::Line { direction, length, .. } => (origin + direction * length / 2., length / 2.),
+                    };
+                    self.particles.emit(SLAM_PARTICLES, center, Vec2::ZERO, radius);
+                    self.camera.on_boss_impact();
+                    if !self.player.is_invulnerable() && shape.contains(origin, self.player.character.world_position) {
+                        let hit = Hit::new(DamageSource::Ennemy, damage, DamageType::Physical);
+                        damage_events.push(deal_damage(hit, &mut self.player, &mut self.rng.rng));
+                    }
+                }
+                BossAction::Ring { center, directions, speed, damage } => {
+                    let bolt = ProjectileKind::Bolt { speed, homing: 0. };
+                    self.ennemy_projectiles.extend(directions.into_iter()
+                        .map(|direction| EnnemyProjectile::new(center, center + direction, bolt, damage,
+                            DamageType::Physical, Vec::new())));
+                }
+                BossAction::Summon { ennemy, positions } => {
+                    // The summoned kind can be gone after a reload
+                    let Some(kind) = self.ennemy_definitions.iter().position(|definition| definition.name == ennemy)
+                    else {
+                        continue;
+                    };
+                    for position in positions {
+                        self.particles.emit(DEATH_PARTICLES, position, Vec2::ZERO, 0.);
+                        self.ennemies.push(Ennemy::new(
+                            position,
+                            compute_normalized_vector(position, self.player.character.world_position),
+                            kind,
+                            &self.ennemy_definitions[kind],
+                            self.progression.level,
+                        ));
+                    }
+                }
+                BossAction::NewPhase { center } => {
+                    self.particles.emit(BOSS_PHASE_PARTICLES, center, Vec2::ZERO, 0.);
+                    self.camera.on_boss_impact();
+                }
+            }
+        }
+        damage_events
+    }
+    
+    /// Move the projectiles of the ennemies, which stop while the ennemies are frozen. Returns their hits.
+    fn update_ennemy_projectiles(&mut self) -> Vec<DamageEvent> {
+        let frozen = self.freeze_timer > 0.;
+        let player_position = self.player.character.world_position;
+        let mut damage_events = Vec::new();
+        self.ennemy_projectiles.retain_mut(|projectile| {
+            let landed = !frozen && projectile.update(player_position);
+            // Projectiles go through the player while it dashes
+            let hits_player = !self.player.is_invulnerable() && collides(projectile, &self.player);
+            if projectile.is_airborne() {
+                if landed {
+                    if let Some(marker) = projectile.marker() && let TelegraphShape::Circle { radius } = marker.shape {
+                        self.particles.emit(BOMB_PARTICLES, marker.world_position, Vec2::ZERO, radius);
+                    }
+                    if hits_player {
+                        let hit = Hit::new(DamageSource::Ennemy, projectile.damage, projectile.damage_type);
+                        damage_events.push(deal_damage(hit, &mut self.player, &mut self.rng.rng));
+                        self.player.statuses.apply_hit(&projectile.effects, 1., &mut self.rng.rng);
+                    }
+                }
+                return !landed;
+            }
+            if hits_player {
+                let hit = Hit::new(DamageSource::Ennemy, projectile.damage, projectile.damage_type);
+                damage_events.push(deal_damage(hit, &mut self.player, &mut self.rng.rng));
+                self.player.statuses.apply_hit(&projectile.effects, 1., &mut self.rng.rng);
+                return false;
+            }
+            !projectile.is_expired() && !self.props.iter().any(|prop| collides(projectile, prop))
+        });
+        damage_events
+    }
+    
+    /// Hit the props touched by the weapons, the broken ones dropping their pickups. Returns the hits.
+    fn hit_props(&mut self) -> Vec<DamageEvent> {
+        let mut damage_events = Vec::new();
+        for prop in self.props.iter_mut() {
+            prop.update();
+            if !prop.can_be_hit() {
+                continue;
+            }
+            let luck = self.player.stats.luck;
+            let mut broken = false;
+            for slot in 0..self.player.weapons.len() {
+                if broken {
+                    break;
+                }
+                let Some(hit) = self.player.weapon_hit(slot, prop) else {
+                    continue;
+                };
+                let event = deal_damage(Hit::weapon(hit.kind, hit.damage, hit.damage_type, luck), prop, &mut self.rng.rng);
+                broken = event.kill;
+                damage_events.push(event);
+            }
+            if !broken {
+                continue;
+            }
+            let definition = &self.prop_definitions.kinds[prop.kind];
+            self.particles.emit(&definition.break_effect, prop.world_position, Vec2::ZERO, 0.);
+            for kind in roll_drops(&definition.drops, self.player.stats.luck, &mut self.prop_rng) {
+                add_pickup(&mut self.pickups, Pickup::new(prop.world_position, kind), MAX_PICKUPS);
+            }
+        }
+        self.props.retain(|prop| prop.hp > 0.);
+        damage_events
+    }
+    
+    /// Collect the pickups the player walks on and apply their effect. Returns the hits of the bombs.
+    fn collect_pickups(&mut self) -> Vec<DamageEvent> {
+        let player_position = self.player.character.world_position;
+        let magnet_radius = self.player.stats.magnet_radius;
+        let mut collected = Vec::new();
+        self.pickups.retain_mut(|pickup| {
+            if pickup.update(player_position, magnet_radius) {
+                collected.push(pickup.kind);
+                return false;
+            }
+            !pickup.is_expired()
+        });
         
-    fn get_input(&mut self) {
-        let mut player_movement: Vec2;
-        let player_direction: Direction;
-        
-        if is_key_down(KeyCode::Down) {
-            player_movement = Vec2::new(0., MOVE_DISTANCE);
-            player_direction = Direction::Down;
-        } else if is_key_down(KeyCode::Up) {
-            player_movement = Vec2::new(0., -MOVE_DISTANCE);
-            player_direction = Direction::Up;
-        } else if is_key_down(KeyCode::Right) {
-            player_movement = Vec2::new(MOVE_DISTANCE, 0.);
-            player_direction = Direction::Right;
-        } else if is_key_down(KeyCode::Left) {
-            player_movement = Vec2::new(-MOVE_DISTANCE, 0.);
-            player_direction = Direction::Left;
+        let mut damage_events = Vec::new();
+        for kind in collected {
+            match kind {
+                PickupKind::Food(hp) => self.player.heal(hp),
+                PickupKind::Magnet => self.xp_gems.iter_mut().for_each(XpGem::attract),
+                PickupKind::Bomb => damage_events.extend(self.explode_bomb()),
+                PickupKind::Freeze => self.freeze_timer = FREEZE_DURATION,
+                PickupKind::Gold(amount) => self.gold += amount,
+            }
+        }
+        damage_events
+    }
+    
+    /// Hit every living ennemy on the screen
+    fn explode_bomb(&mut self) -> Vec<DamageEvent> {
+        let screen = self.unshaken_screen();
+        let mut damage_events = Vec::new();
+        let mut splits = Vec::new();
+        for ennemy in self.ennemies.iter_mut() {
+            if !ennemy.is_alive() || !screen.contains(ennemy.character.world_position) {
+                continue;
+            }
+            let event = deal_damage(Hit::new(DamageSource::Bomb, BOMB_DAMAGE, DamageType::Fire), ennemy, &mut self.rng.rng);
+            if event.kill {
+                splits.extend(ennemy.split().map(|split| (ennemy.kind, ennemy.character.world_position, split)));
+            }
+            damage_events.push(event);
+        }
+        self.spawn_splits(splits);
+        damage_events
+    }
+    
+    /// On-hit and on-kill triggers: the lifesteal of the weapons, the score and the death of the ennemies
+    fn on_damage(&mut self, event: &DamageEvent) {
+        if event.target != DamageTarget::Ennemy {
+            return;
+        }
+        if let DamageSource::Weapon(_) = event.source && self.player.stats.lifesteal > 0. {
+            self.player.heal(event.damage * self.player.stats.lifesteal);
+        }
+        if event.kill {
+            self.score += 1;
+            self.particles.emit(DEATH_PARTICLES, event.world_position, Vec2::ZERO, 0.);
+        }
+    }
+    
+    /// Damage dealt and taken during the run, one line each
+    pub(crate) fn damage_report(&self) -> Vec<String> {
+        self.damage_stats.report()
+    }
+    
+    /// The screen in world coordinates without the camera shake, which doesn't change what the player can see
+    fn unshaken_screen(&self) -> Rect {
+        let player_position = self.player.character.world_position;
+        Rect::new(player_position.x - screen_width() / 2., player_position.y - screen_height() / 2.,
+            screen_width(), screen_height())
+    }
+    
+    /// Sparks flying away from the player when it hits an ennemy, blood when the player is hit.
+    /// Burns and poisons don't hit.
+    fn emit_hit_particles(&mut self, event: &DamageEvent) {
+        if event.is_tick() {
+            return;
+        }
+        if event.to_player() {
+            self.particles.emit(PLAYER_HIT_PARTICLES, event.world_position, Vec2::ZERO, 0.);
         } else {
-            player_movement = Vec2::new(0., 0.);
-            player_direction = Direction::None;
+            let direction = (event.world_position - self.player.character.world_position).normalize_or_zero();
+            self.particles.emit(HIT_PARTICLES, event.world_position, direction, 0.);
         }
+    }
+    
+    /// Continuous effects: gem sparkles, brazier fires, aura motes and dagger trails
+    fn emit_particles(&mut self) {
+        let screen = self.unshaken_screen();
+        for prop in &self.props {
+            if matches!(prop.look, PropLook::Brazier) && screen.contains(prop.world_position) {
+                let fire_position = Vec2 { x: prop.world_position.x, y: prop.world_position.y - prop.size.y / 2. };
+                self.particles.emit(BRAZIER_PARTICLES, fire_position, Vec2 { x: 0., y: -1. }, prop.size.x / 3.);
+            }
+        }
+        for gem in &self.xp_gems {
+            self.particles.emit(GEM_PARTICLES, gem.world_position, Vec2::ZERO, GEM_SPARKLE_RADIUS);
+        }
+        for weapon in &self.player.weapons {
+            match weapon {
+                WeaponSlot::Aura(aura) => {
+                    let center = Vec2 { x: aura.circle.x, y: aura.circle.y };
+                    self.particles.emit(AURA_PARTICLES, center, Vec2 { x: 0., y: -1. }, aura.circle.r);
+                }
+                WeaponSlot::Daggers(daggers) => {
+                    for (position, velocity) in daggers.daggers() {
+                        self.particles.emit(DAGGER_PARTICLES, position, -velocity, 0.);
+                    }
+                }
+                WeaponSlot::Sword(_) => (),
+            }
+        }
+    }
+    
+    /// Attract the gems in the magnet radius and give their experience to the player
+    fn collect_xp_gems(&mut self) {
+        let player_position = self.player.character.world_position;
+        let magnet_radius = self.player.stats.magnet_radius;
+        let mut collected_xp = 0.;
+        self.xp_gems.retain_mut(|gem| {
+            let collected = gem.update(player_position, magnet_radius);
+            if collected {
+                collected_xp += gem.xp as f32;
+            }
+            !collected
+        });
         
-        // Prevent player from moving outside of the map
-        if self.player.character.world_position.x + player_movement.x < 0. {
-            player_movement.x = -self.player.character.world_position.x;
-        } else if self.player.character.world_position.x + player_movement.x > MAP_WIDTH {
-            player_movement.x = MAP_WIDTH - self.player.character.world_position.x;
+        let levels_gained = self.progression.add_xp(collected_xp * self.player.stats.xp_gain);
+        for _ in 0..levels_gained {
+            self.level_up();
+        }
+    }
+    
+    fn level_up(&mut self) {
+        self.grant_random_upgrade();
+        self.player.set_level(self.progression.level);
+    }
+    
+    /// Open the chests the player walks on, giving 1, 3 or 5 rewards depending on luck.
+    /// A weapon that can evolve does so before any other reward.
+    fn open_chests(&mut self) {
+        for i in 0..self.chests.len() {
+            self.chests[i].update();
+            if !self.chests[i].touches(self.player.character.world_position) {
+                continue;
+            }
+            let luck = self.player.stats.luck;
+            let roll = self.rng.rng.random::<f32>();
+            let nb_rewards = if roll < CHEST_FIVE_REWARDS_CHANCE * luck {
+                5
+            } else if roll < CHEST_THREE_REWARDS_CHANCE * luck {
+                3
+            } else {
+                1
+            };
+            let rewards: Vec<String> = (0..nb_rewards)
+                .filter_map(|_| self.evolve_weapon().or_else(|| self.grant_random_upgrade().map(|upgrade| upgrade.label())))
+                .collect();
+            let chest = &mut self.chests[i];
+            chest.open(rewards);
+            self.particles.emit(CHEST_PARTICLES, chest.world_position, Vec2 { x: 0., y: -1. }, 0.);
+        }
+        self.chests.retain(|chest| !chest.is_opened());
+    }
+    
+    /// Evolve the first weapon meeting the needs of its evolution, returning the label of the reward
+    fn evolve_weapon(&mut self) -> Option<String> {
+        let evolution = self.weapon_definitions.evolutions.iter()
+            .find(|evolution| self.player.can_evolve(evolution))?
+            .clone();
+        self.player.evolve(&evolution);
+        Some(format!("Evolution : {}", evolution.name))
+    }
+    
+    /// Give the player a random upgrade among the ones it can still get
+    fn grant_random_upgrade(&mut self) -> Option<Upgrade> {
+        let available_upgrades: Vec<Upgrade> = Upgrade::all().into_iter()
+            .filter(|upgrade| self.player.can_apply(*upgrade))
+            .collect();
+        let upgrade = *available_upgrades.choose(&mut self.rng.rng)?;
+        match upgrade {
+            Upgrade::Weapon(kind) if !self.player.has_weapon(kind) => {
+                let weapon = new_weapon(kind, self.player.character.world_position,
+                    &self.weapon_definitions, &self.textures);
+                self.player.add_weapon(weapon);
+            }
+            _ => self.player.apply_upgrade(upgrade),
         }
-        if self.player.character.world_position.y + player_movement.y < 0. {
-            player_movement.y = -self.player.character.world_position.y;
-        } else if self.player.character.world_position.y + player_movement.y > MAP_HEIGHT {
-            player_movement.y = MAP_HEIGHT - self.player.character.world_position.y;
+        self.last_upgrade = Some(upgrade);
+        Some(upgrade)
+    }
+        
+    fn get_input(&mut self, input_map: &InputMap) {
+        let move_axis = input_map.move_axis();
+        // Frozen and stunned players can't dash nor attack
+        let can_act = self.player.statuses.can_act();
+        
+        if can_act && input_map.is_pressed(InputAction::Dash) {
+            // Dash where the player is going, or where it was going before stopping
+            let dash_direction = if move_axis != Vec2::ZERO { move_axis } else { self.player.facing };
+            self.player.dash.start(dash_direction);
         }
+        let dash_movement = self.player.dash.update(self.player.character.world_position);
+        
+        let player_movement = if self.player.dash.is_dashing() {
+            dash_movement
+        } else {
+            move_axis * MOVE_DISTANCE * self.player.stats.move_speed
+        };
+        let player_movement = self.player.statuses.movement(player_movement, get_frame_time());
+        let player_movement = clamp_movement_to_map(self.player.character.world_position, player_movement);
+        let player_movement = slide_movement_along_props(self.player.character.feet(), player_movement, &self.props);
+        let player_direction = get_direction_from_vector(player_movement);
 
         self.player.move_by(player_movement, player_direction);
 
         adjust_ennemies_velocity(&mut self.ennemies, &self.player);
         
         
-        if is_key_pressed(KeyCode::Space) {
-            let mut mouse_pos = Vec2::new(0., 0.);
-            (mouse_pos.x, mouse_pos.y) = mouse_position();
-
-            println!("Mouse position: {:?}", mouse_pos);
-            
-            let normalize_vect = compute_normalized_vector(
-                Vec2{x: screen_width() / 2., y: screen_height() / 2.}, mouse_pos);
+        if can_act && input_map.is_pressed(InputAction::Attack) {
+            // Aim with the right stick when it is used, with the mouse otherwise
+            let normalize_vect = input_map.aim_axis().unwrap_or_else(|| {
+                let mut mouse_pos = Vec2::new(0., 0.);
+                (mouse_pos.x, mouse_pos.y) = mouse_position();
+                compute_normalized_vector(
+                    Vec2{x: screen_width() / 2., y: screen_height() / 2.}, mouse_pos)
+            });
                 
-            self.player.throw_dagger(normalize_vect, normalize_vect.y.atan2(normalize_vect.x));
+            self.player.attack(normalize_vect);
         }
     }
             
-    fn draw(&mut self) {
+    fn draw(&self, settings: &Settings, assets: &AssetManager) {
         // Screen origin (upper left corner) in world coordinates
-        let screen_origin_position = Vec2{
-            x: self.player.character.world_position.x - screen_width() / 2.,
-            y: self.player.character.world_position.y - screen_height() / 2.,
-        };
+        let screen_origin_position = self.camera.screen_origin(self.player.character.world_position, settings);
+        let flash_material = self.feedback.flash_material(settings);
+        let silhouette_material = self.feedback.silhouette_material();
+        let mut queue = RenderQueue::new();
 
-        let screen_rect = Rect::new(
-            screen_origin_position.x, 
-            screen_origin_position.y, 
-            screen_width(), 
-            screen_height()
-        );
-
-        draw_texture_ex(&self.grass_texture, 0., 0., WHITE, DrawTextureParams {
-            source: Some(screen_rect),
-            ..Default::default()
+        queue.push(Layer::Ground, 0., move || {
+            let screen_rect = Rect::new(
+                screen_origin_position.x, 
+                screen_origin_position.y, 
+                screen_width(), 
+                screen_height()
+            );
+            draw_texture_ex(assets.texture(self.textures.grass), 0., 0., WHITE, DrawTextureParams {
+                source: Some(screen_rect),
+                ..Default::default()
+            });
         });
-
-        for ennemy in self.ennemies.iter_mut() {
-            ennemy.draw(screen_origin_position, &self.orc_texture);
+        for gem in &self.xp_gems {
+            queue.push(Layer::Decals, 0., move || gem.draw(screen_origin_position));
+        }
+        for pickup in &self.pickups {
+            queue.push(Layer::Decals, 0., move || pickup.draw(screen_origin_position));
+        }
+        for prop in &self.props {
+            queue.push(Layer::Actors, prop.foot_y(), move || prop.draw(screen_origin_position));
+        }
+        for chest in &self.chests {
+            queue.push(Layer::Actors, chest.foot_y(), move || chest.draw(screen_origin_position));
+            queue.push(Layer::Effects, 0., move || chest.draw_rewards(screen_origin_position));
+        }
+        for ennemy in &self.ennemies {
+            let sprite_handle = self.textures.ennemies[ennemy.kind][ennemy.character.clip()];
+            queue.push(Layer::Decals, 0., move || ennemy.draw_corpse(screen_origin_position));
+            if let Some(brain) = &ennemy.boss && let Some(boss) = &self.ennemy_definitions[ennemy.kind].boss
+                && ennemy.is_alive() {
+                queue.push(Layer::Decals, 0., move || brain.draw_telegraph(boss, screen_origin_position));
+            }
+            if let Some(aim) = ennemy.aim() && ennemy.is_alive() {
+                queue.push(Layer::Decals, 0., move || aim.draw(screen_origin_position, MARKER_COLOR));
+            }
+            queue.push(Layer::Actors, ennemy.character.foot_y(), move || {
+                ennemy.draw(screen_origin_position, &assets.sprite(sprite_handle), flash_material, silhouette_material);
+            });
+        }
+        for projectile in &self.ennemy_projectiles {
+            if let Some(marker) = projectile.marker() {
+                queue.push(Layer::Decals, 0., move || marker.draw(screen_origin_position, MARKER_COLOR));
+            }
+            queue.push(Layer::Projectiles, 0., move || projectile.draw(screen_origin_position));
+        }
+        self.player.queue_draw(&mut queue, screen_origin_position, assets, settings.dash_afterimages, flash_material);
+        queue.push(Layer::Effects, 0., move || {
+            self.particles.draw(screen_origin_position, assets);
+            self.feedback.draw(screen_origin_position);
+            self.camera.draw_vignettes(self.player.character.hp / self.player.stats.max_hp);
+            if self.freeze_timer > 0. {
+                draw_rectangle(0., 0., screen_width(), screen_height(), FREEZE_COLOR);
+            }
+        });
+        let ui_scale = settings.ui_scale();
+        queue.push(Layer::Hud, 0., move || {
+            Hud::new(assets, self.textures.font, ui_scale).draw(&self.hud_info(), screen_origin_position);
+        });
+        if settings.minimap {
+            let minimap_size = settings.minimap_size;
+            queue.push(Layer::Hud, 0., move || {
+                draw_minimap(&self.minimap_info(screen_origin_position), minimap_size, ui_scale);
+            });
+        }
+        queue.draw();
+    }
+    
+    fn hud_info(&self) -> HudInfo<'_> {
+        let bosses = self.ennemies.iter()
+            .filter(|ennemy| ennemy.is_alive() && ennemy.boss.is_some())
+            .map(|ennemy| BossBar {
+                name: &self.ennemy_definitions[ennemy.kind].name,
+                hp_ratio: ennemy.character.hp / ennemy.max_hp,
+            })
+            .collect();
+        HudInfo {
+            player: &self.player,
+            progression: &self.progression,
+            time: self.time,
+            kills: self.score,
+            gold: self.gold,
+            last_upgrade: self.last_upgrade,
+            bosses,
+        }
+    }
+            
+    fn minimap_info(&self, screen_origin_position: Vec2) -> MinimapInfo {
+        let (bosses, ennemies): (Vec<&Ennemy>, Vec<&Ennemy>) = self.ennemies.iter()
+            .filter(|ennemy| ennemy.is_alive())
+            .partition(|ennemy| ennemy.boss.is_some());
+        MinimapInfo {
+            map: map_rect(),
+            view: Rect::new(screen_origin_position.x, screen_origin_position.y, screen_width(), screen_height()),
+            player: self.player.character.world_position,
+            ennemies: ennemies.iter().map(|ennemy| ennemy.character.world_position).collect(),
+            bosses: bosses.iter().map(|ennemy| ennemy.character.world_position).collect(),
+            points_of_interest: self.chests.iter()
+                .filter(|chest| chest.is_closed())
+                .map(|chest| chest.world_position)
+                .collect(),
         }
-        self.player.draw(screen_origin_position, &self.player_idle_texture, &self.player_walking_texture);
-        draw_text(&format!("Score : {}", self.score), 10., 15., 20., WHITE);
-        draw_text(&format!("HP : {}", self.player.character.hp), 10., 32., 20., WHITE);
     }
             
+    /// Spawn random ennemies among the ones unlocked at the player level, bosses aside
     fn populate_ennemies(&mut self) {
-        while self.ennemies.len() < MAX_ENNEMIES_NB.into() {
+        let level = self.progression.level;
+        let unlocked_kinds: Vec<usize> = (0..self.ennemy_definitions.len())
+            .filter(|kind| {
+                let definition = &self.ennemy_definitions[*kind];
+                definition.boss.is_none() && definition.min_level <= level
+            })
+            .collect();
+        // Dying ennemies, corpses and bosses don't prevent new ones from spawning
+        let nb_alive = self.ennemies.iter().filter(|ennemy| ennemy.is_alive() && ennemy.boss.is_none()).count();
+        for _ in nb_alive..MAX_ENNEMIES_NB.into() {
             let new_ennemy_pos = Vec2 { 
                 x: self.rng.x_pos_gen.sample(&mut self.rng.rng), 
                 y: self.rng.y_pos_gen.sample(&mut self.rng.rng) 
             };
-            self.ennemies.push(Ennemy::new( 
+            // The definitions are validated so that at least one ennemy that isn't a boss is unlocked at level 1
+            let kind = *unlocked_kinds.choose(&mut self.rng.rng).unwrap();
+            let mut ennemy = Ennemy::new( 
                 new_ennemy_pos, 
                 compute_normalized_vector(new_ennemy_pos, self.player.character.world_position),
+                kind,
+                &self.ennemy_definitions[kind],
+                level,
+            );
+            let modifiers = self.elite_definitions.roll(self.time, &mut self.rng.rng);
+            if !modifiers.is_empty() {
+                // Outlined with the blend of the colors of its modifiers
+                let outline = modifiers.iter()
+                    .map(|modifier| modifier.color().to_vec())
+                    .sum::<Vec4>() / modifiers.len() as f32;
+                let modifiers = modifiers.iter().map(|modifier| modifier.modifier).collect();
+                ennemy.make_elite(modifiers, Color::from_vec(outline), &self.ennemy_definitions[kind]);
+            }
+            self.ennemies.push(ennemy);
+        }
+    }
+    
+    /// Spawn the smaller ennemies splitting from the killed splitting elites, given with their kind,
+    /// where they died, how many ennemies they split into and the fraction of their max HP these get
+    fn spawn_splits(&mut self, splits: Vec<(usize, Vec2, (u8, f32))>) {
+        let player_position = self.player.character.world_position;
+        for (kind, position, (count, hp_ratio)) in splits {
+            for i in 0..count {
+                let direction = Vec2::from_angle(TAU * i as f32 / count as f32);
+                let position = position + clamp_movement_to_map(position, direction * SPLIT_DISTANCE);
+                let mut ennemy = Ennemy::new(
+                    position,
+                    compute_normalized_vector(position, player_position),
+                    kind,
+                    &self.ennemy_definitions[kind],
+                    self.progression.level,
+                );
+                ennemy.shrink(hp_ratio);
+                self.ennemies.push(ennemy);
+            }
+        }
+    }
+    
+    /// Spawn the bosses whose time has come, out of the screen around the player
+    fn spawn_bosses(&mut self) {
+        for kind in 0..self.ennemy_definitions.len() {
+            let Some(boss) = &self.ennemy_definitions[kind].boss else {
+                continue;
+            };
+            if self.time < self.boss_spawns[kind] {
+                continue;
+            }
+            self.boss_spawns[kind] = if boss.respawn_interval > 0. {
+                self.boss_spawns[kind] + boss.respawn_interval
+            } else {
+                f32::INFINITY
+            };
+            let player_position = self.player.character.world_position;
+            let map = map_rect();
+            let rng = &mut self.rng.rng;
+            // Near the edges of the map, the directions pointing out of it would drop the boss on the player
+            let direction = (0..BOSS_SPAWN_TRIES)
+                .map(|_| Vec2::from_angle(rng.random_range(0.0..TAU)))
+                .find(|direction| map.contains(player_position + *direction * BOSS_SPAWN_DISTANCE))
+                // The middle of the map always has room
+                .unwrap_or_else(|| (map.center() - player_position).normalize_or(Vec2 { x: 1., y: 0. }));
+            let position = player_position + clamp_movement_to_map(player_position, direction * BOSS_SPAWN_DISTANCE);
+            self.ennemies.push(Ennemy::new(
+                position,
+                compute_normalized_vector(position, player_position),
+                kind,
+                &self.ennemy_definitions[kind],
+                self.progression.level,
             ));
         }
     }
 }
         
-fn adjust_ennemies_velocity(ennemies: &mut Vec<Ennemy>, player: &Player) {
+/// Time of the next spawn of each boss after `time`, infinite for the other kinds and the bosses spawning once
+/// that already have
+fn boss_spawn_times(definitions: &[EnnemyDefinition], time: f32) -> Vec<f32> {
+    definitions.iter()
+        .map(|definition| match &definition.boss {
+            Some(boss) if time <= boss.spawn_time => boss.spawn_time,
+            Some(boss) if boss.respawn_interval > 0. => {
+                let respawns = ((time - boss.spawn_time) / boss.respawn_interval).ceil();
+                boss.spawn_time + respawns * boss.respawn_interval
+            }
+            _ => f32::INFINITY,
+        })
+        .collect()
+}
+
+fn new_weapon(kind: WeaponKind, position: Vec2, definitions: &WeaponDefinitions, textures: &GameTextures) -> WeaponSlot {
+    match kind {
+        WeaponKind::Sword => WeaponSlot::Sword(Sword::new(position, &definitions.sword, textures.sword)),
+        WeaponKind::Daggers => WeaponSlot::Daggers(DaggerAggregate::new(&definitions.daggers, textures.dagger)),
+        WeaponKind::Aura => WeaponSlot::Aura(Aura::new(position, &definitions.aura)),
+    }
+}
+
+fn map_rect() -> Rect {
+    Rect::new(0., 0., MAP_WIDTH, MAP_HEIGHT)
+}
+
+/// Shorten a movement so it doesn't take the player outside of the map
+fn clamp_movement_to_map(world_position: Vec2, movement: Vec2) -> Vec2 {
+    let mut movement = movement;
+    if world_position.x + movement.x < 0. {
+        movement.x = -world_position.x;
+    } else if world_position.x + movement.x > MAP_WIDTH {
+        movement.x = MAP_WIDTH - world_position.x;
+    }
+    if world_position.y + movement.y < 0. {
+        movement.y = -world_position.y;
+    } else if world_position.y + movement.y > MAP_HEIGHT {
+        movement.y = MAP_HEIGHT - world_position.y;
+    }
+    movement
+}
+        
+/// Stop the feet of the player at the base of the blocking props, keeping the part of the movement
+/// along them so the player slides around. Feet already inside a prop can move freely to get out.
+fn slide_movement_along_props(feet: Rect, movement: Vec2, props: &[Prop]) -> Vec2 {
+    let blocks = |movement: Vec2| {
+        let moved_feet = feet.offset(movement);
+        props.iter().any(|prop| prop.blocking && prop.base().overlaps(&moved_feet) && !prop.base().overlaps(&feet))
+    };
+    [movement, Vec2 { x: movement.x, y: 0. }, Vec2 { x: 0., y: movement.y }]
+        .into_iter()
+        .find(|movement| !blocks(*movement))
+        .unwrap_or(Vec2::ZERO)
+}
+
+fn adjust_ennemies_velocity(ennemies: &mut [Ennemy], player: &Player) {
     for ennemy in ennemies.iter_mut() {
         ennemy.vel = compute_normalized_vector(ennemy.character.world_position, player.character.world_position);
     }
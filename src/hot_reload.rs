@@ -0,0 +1,45 @@
+use crate::asset_manager::AssetManager;
+use crate::data::Definitions;
+use crate::entity::character_definition::{ROSTER_PATH, load_roster};
+use crate::entity::ennemy_definition::{ENNEMIES_PATH, load_ennemy_definitions};
+use crate::entity::prop_definition::{PROPS_PATH, PropDefinitions};
+use crate::entity::elite::{ELITES_PATH, EliteDefinitions};
+use crate::game::Game;
+use crate::particles::emitter_definition::{PARTICLES_PATH, load_emitter_definitions};
+use crate::survivor_ui::toast::Toasts;
+use crate::weapons::weapon_definition::{WEAPONS_PATH, WeaponDefinitions};
+
+/// Reload the assets and data definitions modified since the last check, and apply them to the current run.
+/// When a file can't be reloaded its previous version is kept and the error is shown in a toast.
+/// Only called in debug builds.
+pub(crate) async fn hot_reload(assets: &mut AssetManager, definitions: &mut Definitions, game: &mut Game,
+    character: &mut usize, toasts: &mut Toasts) {
+    let mut definitions_changed = false;
+    for path in assets.changed_files() {
+        let result = match path.as_str() {
+            ROSTER_PATH => load_roster().await.map(|roster| definitions.roster = roster),
+            WEAPONS_PATH => WeaponDefinitions::load().await.map(|weapons| definitions.weapons = weapons),
+            ENNEMIES_PATH => load_ennemy_definitions().await.map(|ennemies| definitions.ennemies = ennemies),
+            PARTICLES_PATH => load_emitter_definitions().await.map(|particles| definitions.particles = particles),
+            PROPS_PATH => PropDefinitions::load().await.map(|props| definitions.props = props),
+            ELITES_PATH => EliteDefinitions::load().await.map(|elites| definitions.elites = elites),
+            _ => assets.reload(&path).await,
+        };
+        match result {
+            Ok(()) => {
+                definitions_changed |= [ROSTER_PATH, WEAPONS_PATH, ENNEMIES_PATH, PARTICLES_PATH, PROPS_PATH, ELITES_PATH].contains(&path.as_str());
+                toasts.info(format!("Reloaded {path}"));
+            }
+            Err(error) => toasts.error(error),
+        }
+    }
+
+    if definitions_changed {
+        // The character may have been removed from the roster
+        *character = (*character).min(definitions.roster.len() - 1);
+        // Definitions may use textures which weren't loaded yet
+        let textures = definitions.textures(*character, assets);
+        assets.load_pending().await;
+        game.apply_definitions(definitions, *character, textures);
+    }
+}
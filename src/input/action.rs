@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// Named actions the game reacts to, independently of the keys bound to them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub(crate) enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Attack,
+    Dash,
+    Pause,
+    Confirm,
+    ToggleMinimap,
+}
+
+impl InputAction {
+    /// Every action, in the order they are listed on the options screen
+    pub(crate) const ALL: [InputAction; 9] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Attack,
+        InputAction::Dash,
+        InputAction::Pause,
+        InputAction::Confirm,
+        InputAction::ToggleMinimap,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move up",
+            InputAction::MoveDown => "Move down",
+            InputAction::MoveLeft => "Move left",
+            InputAction::MoveRight => "Move right",
+            InputAction::Attack => "Attack",
+            InputAction::Dash => "Dash",
+            InputAction::Pause => "Pause",
+            InputAction::Confirm => "Confirm",
+            InputAction::ToggleMinimap => "Minimap",
+        }
+    }
+}
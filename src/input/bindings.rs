@@ -0,0 +1,157 @@
+use std::{collections::BTreeMap, mem};
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::input::action::InputAction;
+
+/// Keys that can be bound to an action. They are saved by name, so this list is also
+/// used to read them back from the settings file.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl,
+    KeyCode::LeftAlt, KeyCode::RightAlt,
+];
+
+/// Gamepad buttons, named after their position so they match every controller layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+const GAMEPAD_BUTTONS: &[GamepadButton] = &[
+    GamepadButton::South, GamepadButton::East, GamepadButton::West, GamepadButton::North,
+    GamepadButton::LeftBumper, GamepadButton::RightBumper,
+    GamepadButton::LeftTrigger, GamepadButton::RightTrigger,
+    GamepadButton::Select, GamepadButton::Start,
+    GamepadButton::DPadUp, GamepadButton::DPadDown, GamepadButton::DPadLeft, GamepadButton::DPadRight,
+];
+
+// Gamepad buttons are saved with this prefix so they can't be mistaken for keys
+const GAMEPAD_PREFIX: &str = "Pad";
+
+/// A physical input that can trigger an action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Binding {
+    Key(KeyCode),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    /// Build a binding from a key, if that key can be bound at all
+    pub(crate) fn from_key(key: KeyCode) -> Option<Self> {
+        BINDABLE_KEYS.contains(&key).then_some(Binding::Key(key))
+    }
+
+    pub(crate) fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{key:?}"),
+            Binding::Gamepad(button) => format!("{GAMEPAD_PREFIX}{button:?}"),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        if let Some(button_name) = name.strip_prefix(GAMEPAD_PREFIX) {
+            return GAMEPAD_BUTTONS.iter()
+                .find(|button| format!("{button:?}") == button_name)
+                .map(|button| Binding::Gamepad(*button));
+        }
+        BINDABLE_KEYS.iter()
+            .find(|key| format!("{key:?}") == name)
+            .map(|key| Binding::Key(*key))
+    }
+}
+
+impl Serialize for Binding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Binding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Binding::from_name(&name).ok_or_else(|| de::Error::custom(format!("unknown binding `{name}`")))
+    }
+}
+
+/// Every binding of every action. An action can have several bindings, the first one
+/// being the primary binding that is replaced when rebinding from the options screen.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct InputBindings {
+    actions: BTreeMap<InputAction, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let mut actions = BTreeMap::new();
+        for action in InputAction::ALL {
+            actions.insert(action, default_bindings(action));
+        }
+        Self { actions }
+    }
+}
+
+impl InputBindings {
+    pub(crate) fn get(&self, action: InputAction) -> &[Binding] {
+        self.actions.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Replace the primary binding of an action on the device of the new binding
+    /// (keyboard or gamepad), keeping its alternative bindings
+    pub(crate) fn set_primary(&mut self, action: InputAction, binding: Binding) {
+        let bindings = self.actions.entry(action).or_default();
+        bindings.retain(|existing| *existing != binding);
+        let same_device = bindings.iter()
+            .position(|existing| mem::discriminant(existing) == mem::discriminant(&binding));
+        match same_device {
+            Some(index) => bindings[index] = binding,
+            None => bindings.push(binding),
+        }
+    }
+
+    /// Give their default bindings to the actions missing from a settings file written
+    /// by an older version of the game
+    pub(crate) fn fill_missing(&mut self) {
+        for action in InputAction::ALL {
+            self.actions.entry(action).or_insert_with(|| default_bindings(action));
+        }
+    }
+}
+
+fn default_bindings(action: InputAction) -> Vec<Binding> {
+    let (keys, buttons): (&[KeyCode], &[GamepadButton]) = match action {
+        InputAction::MoveUp => (&[KeyCode::W, KeyCode::Up], &[GamepadButton::DPadUp]),
+        InputAction::MoveDown => (&[KeyCode::S, KeyCode::Down], &[GamepadButton::DPadDown]),
+        InputAction::MoveLeft => (&[KeyCode::A, KeyCode::Left], &[GamepadButton::DPadLeft]),
+        InputAction::MoveRight => (&[KeyCode::D, KeyCode::Right], &[GamepadButton::DPadRight]),
+        InputAction::Attack => (&[KeyCode::Space], &[GamepadButton::RightTrigger, GamepadButton::RightBumper]),
+        InputAction::Dash => (&[KeyCode::LeftShift, KeyCode::RightShift], &[GamepadButton::East]),
+        InputAction::Pause => (&[KeyCode::Escape, KeyCode::P], &[GamepadButton::Start]),
+        InputAction::Confirm => (&[KeyCode::Enter, KeyCode::Space], &[GamepadButton::South]),
+        InputAction::ToggleMinimap => (&[KeyCode::M, KeyCode::Tab], &[GamepadButton::Select]),
+    };
+    keys.iter().map(|key| Binding::Key(*key))
+        .chain(buttons.iter().map(|button| Binding::Gamepad(*button)))
+        .collect()
+}
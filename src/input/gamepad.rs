@@ -0,0 +1,113 @@
+use gilrs::{Axis, Button, EventType, Gamepad, Gilrs};
+use macroquad::prelude::*;
+
+use crate::input::{bindings::{Binding, GamepadButton}, source::{InputSource, apply_deadzone}};
+
+/// Reads the first connected gamepad
+pub(crate) struct GamepadSource {
+    gilrs: Gilrs,
+    // Buttons pressed since the last update
+    pressed: Vec<GamepadButton>,
+}
+
+impl GamepadSource {
+    /// Returns `None` when no gamepad backend is available on this platform
+    pub(crate) fn new() -> Option<Self> {
+        match Gilrs::new() {
+            Ok(gilrs) => Some(Self { gilrs, pressed: Vec::new() }),
+            Err(error) => {
+                println!("Gamepad support disabled: {error}");
+                None
+            }
+        }
+    }
+
+    fn gamepad(&self) -> Option<Gamepad<'_>> {
+        self.gilrs.gamepads().map(|(_, gamepad)| gamepad).next()
+    }
+
+    fn stick(&self, x_axis: Axis, y_axis: Axis) -> Vec2 {
+        match self.gamepad() {
+            // Gamepad sticks have y pointing up
+            Some(gamepad) => apply_deadzone(Vec2::new(gamepad.value(x_axis), -gamepad.value(y_axis))),
+            None => Vec2::ZERO,
+        }
+    }
+}
+
+impl InputSource for GamepadSource {
+    fn update(&mut self) {
+        self.pressed.clear();
+        while let Some(event) = self.gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event
+                && let Some(button) = from_gilrs(button) {
+                self.pressed.push(button);
+            }
+        }
+    }
+
+    fn is_down(&self, binding: Binding) -> bool {
+        match (binding, self.gamepad()) {
+            (Binding::Gamepad(button), Some(gamepad)) => gamepad.is_pressed(to_gilrs(button)),
+            _ => false,
+        }
+    }
+
+    fn is_pressed(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Gamepad(button) => self.pressed.contains(&button),
+            _ => false,
+        }
+    }
+
+    fn last_pressed(&self) -> Option<Binding> {
+        self.pressed.last().map(|button| Binding::Gamepad(*button))
+    }
+
+    fn left_stick(&self) -> Vec2 {
+        self.stick(Axis::LeftStickX, Axis::LeftStickY)
+    }
+
+    fn right_stick(&self) -> Vec2 {
+        self.stick(Axis::RightStickX, Axis::RightStickY)
+    }
+}
+
+fn to_gilrs(button: GamepadButton) -> Button {
+    match button {
+        GamepadButton::South => Button::South,
+        GamepadButton::East => Button::East,
+        GamepadButton::West => Button::West,
+        GamepadButton::North => Button::North,
+        GamepadButton::LeftBumper => Button::LeftTrigger,
+        GamepadButton::RightBumper => Button::RightTrigger,
+        GamepadButton::LeftTrigger => Button::LeftTrigger2,
+        GamepadButton::RightTrigger => Button::RightTrigger2,
+        GamepadButton::Select => Button::Select,
+        GamepadButton::Start => Button::Start,
+        GamepadButton::DPadUp => Button::DPadUp,
+        GamepadButton::DPadDown => Button::DPadDown,
+        GamepadButton::DPadLeft => Button::DPadLeft,
+        GamepadButton::DPadRight => Button::DPadRight,
+    }
+}
+
+fn from_gilrs(button: Button) -> Option<GamepadButton> {
+    match button {
+        Button::South => Some(GamepadButton::South),
+        Button::East => Some(GamepadButton::East),
+        Button::West => Some(GamepadButton::West),
+        Button::North => Some(GamepadButton::North),
+        Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+        Button::RightTrigger => Some(GamepadButton::RightBumper),
+        Button::LeftTrigger2 => Some(GamepadButton::LeftTrigger),
+        Button::RightTrigger2 => Some(GamepadButton::RightTrigger),
+        Button::Select => Some(GamepadButton::Select),
+        Button::Start => Some(GamepadButton::Start),
+        Button::DPadUp => Some(GamepadButton::DPadUp),
+        Button::DPadDown => Some(GamepadButton::DPadDown),
+        Button::DPadLeft => Some(GamepadButton::DPadLeft),
+        Button::DPadRight => Some(GamepadButton::DPadRight),
+        _ => None,
+    }
+}
@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+
+use crate::input::{action::InputAction, bindings::{Binding, InputBindings}, source::{InputSource, KeyboardSource}};
+
+const MENU_STICK_THRESHOLD: f32 = 0.5; // Stick position that moves the menu focus by one button
+
+/// Action map: answers "is this action active" by checking every binding of the action
+/// on every input source
+pub(crate) struct InputMap {
+    pub(crate) bindings: InputBindings,
+    sources: Vec<Box<dyn InputSource>>,
+    // Vertical left stick position on the previous frame, to move the menu focus once per push
+    previous_stick_y: f32,
+    menu_step: i32,
+}
+
+impl InputMap {
+    pub(crate) fn new(bindings: InputBindings) -> Self {
+        #[allow(unused_mut)]
+        let mut sources: Vec<Box<dyn InputSource>> = vec![Box::new(KeyboardSource)];
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepad) = crate::input::gamepad::GamepadSource::new() {
+            sources.push(Box::new(gamepad));
+        }
+        Self::with_sources(bindings, sources)
+    }
+
+    pub(crate) fn with_sources(bindings: InputBindings, sources: Vec<Box<dyn InputSource>>) -> Self {
+        Self {
+            bindings,
+            sources,
+            previous_stick_y: 0.,
+            menu_step: 0,
+        }
+    }
+
+    /// Must be called once per frame, before reading any input
+    pub(crate) fn update(&mut self) {
+        for source in self.sources.iter_mut() {
+            source.update();
+        }
+
+        let stick_y = self.left_stick().y;
+        self.menu_step = if stick_y.abs() > MENU_STICK_THRESHOLD && self.previous_stick_y.abs() <= MENU_STICK_THRESHOLD {
+            stick_y.signum() as i32
+        } else {
+            0
+        };
+        self.previous_stick_y = stick_y;
+    }
+
+    pub(crate) fn is_down(&self, action: InputAction) -> bool {
+        self.bindings.get(action).iter()
+            .any(|binding| self.sources.iter().any(|source| source.is_down(*binding)))
+    }
+
+    pub(crate) fn is_pressed(&self, action: InputAction) -> bool {
+        self.bindings.get(action).iter()
+            .any(|binding| self.sources.iter().any(|source| source.is_pressed(*binding)))
+    }
+
+    /// Binding pressed this frame on any source
+    pub(crate) fn last_pressed(&self) -> Option<Binding> {
+        self.sources.iter().find_map(|source| source.last_pressed())
+    }
+
+    /// Movement direction built from the left stick or, when it is at rest, from every move
+    /// action held at the same time. The result never exceeds a length of 1 so moving
+    /// diagonally isn't faster than moving straight.
+    pub(crate) fn move_axis(&self) -> Vec2 {
+        let stick = self.left_stick();
+        if stick != Vec2::ZERO {
+            return stick.clamp_length_max(1.);
+        }
+
+        let mut axis = Vec2::ZERO;
+        if self.is_down(InputAction::MoveUp) {
+            axis.y -= 1.;
+        }
+        if self.is_down(InputAction::MoveDown) {
+            axis.y += 1.;
+        }
+        if self.is_down(InputAction::MoveLeft) {
+            axis.x -= 1.;
+        }
+        if self.is_down(InputAction::MoveRight) {
+            axis.x += 1.;
+        }
+        axis.normalize_or_zero()
+    }
+
+    /// Aiming direction given by the right stick, `None` when the stick is at rest
+    pub(crate) fn aim_axis(&self) -> Option<Vec2> {
+        self.sources.iter()
+            .map(|source| source.right_stick())
+            .find(|stick| *stick != Vec2::ZERO)
+            .map(Vec2::normalize)
+    }
+
+    /// Focus movement in menus: -1 to go to the previous button, 1 for the next one
+    pub(crate) fn menu_step(&self) -> i32 {
+        if self.is_pressed(InputAction::MoveUp) {
+            -1
+        } else if self.is_pressed(InputAction::MoveDown) {
+            1
+        } else {
+            self.menu_step
+        }
+    }
+
+    fn left_stick(&self) -> Vec2 {
+        self.sources.iter()
+            .map(|source| source.left_stick())
+            .find(|stick| *stick != Vec2::ZERO)
+            .unwrap_or(Vec2::ZERO)
+    }
+}
@@ -0,0 +1,8 @@
+pub(crate) mod action;
+pub(crate) mod bindings;
+pub(crate) mod input_map;
+pub(crate) mod source;
+#[cfg(feature = "gamepad")]
+pub(crate) mod gamepad;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,96 @@
+use macroquad::prelude::*;
+
+use crate::input::bindings::Binding;
+
+#[cfg(any(test, feature = "gamepad"))]
+const STICK_DEADZONE: f32 = 0.2; // Stick positions below this length are ignored
+
+/// A device the action map reads its inputs from
+pub(crate) trait InputSource {
+    /// Called once per frame, before any input is read
+    fn update(&mut self) {}
+
+    fn is_down(&self, binding: Binding) -> bool;
+
+    fn is_pressed(&self, binding: Binding) -> bool;
+
+    /// Binding pressed this frame, used to rebind actions from the options screen
+    fn last_pressed(&self) -> Option<Binding>;
+
+    /// Left stick position with the deadzone applied, y pointing down like the screen
+    fn left_stick(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    /// Right stick position with the deadzone applied, y pointing down like the screen
+    fn right_stick(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+}
+
+/// Ignore the stick noise around its rest position and rescale the rest of the range
+/// so the stick still goes smoothly from 0 to 1
+#[cfg(any(test, feature = "gamepad"))]
+pub(crate) fn apply_deadzone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length < STICK_DEADZONE {
+        return Vec2::ZERO;
+    }
+    let rescaled_length = ((length - STICK_DEADZONE) / (1.0 - STICK_DEADZONE)).min(1.0);
+    stick / length * rescaled_length
+}
+
+pub(crate) struct KeyboardSource;
+
+impl InputSource for KeyboardSource {
+    fn is_down(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(key) => is_key_down(key),
+            _ => false,
+        }
+    }
+
+    fn is_pressed(&self, binding: Binding) -> bool {
+        match binding {
+            Binding::Key(key) => is_key_pressed(key),
+            _ => false,
+        }
+    }
+
+    fn last_pressed(&self) -> Option<Binding> {
+        get_last_key_pressed().and_then(Binding::from_key)
+    }
+}
+
+/// Scripted input source, used to drive the action map without any real device
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct MockInputSource {
+    pub(crate) down: Vec<Binding>,
+    pub(crate) pressed: Vec<Binding>,
+    pub(crate) left_stick: Vec2,
+    pub(crate) right_stick: Vec2,
+}
+
+#[cfg(test)]
+impl InputSource for MockInputSource {
+    fn is_down(&self, binding: Binding) -> bool {
+        self.down.contains(&binding)
+    }
+
+    fn is_pressed(&self, binding: Binding) -> bool {
+        self.pressed.contains(&binding)
+    }
+
+    fn last_pressed(&self) -> Option<Binding> {
+        self.pressed.last().copied()
+    }
+
+    fn left_stick(&self) -> Vec2 {
+        apply_deadzone(self.left_stick)
+    }
+
+    fn right_stick(&self) -> Vec2 {
+        apply_deadzone(self.right_stick)
+    }
+}
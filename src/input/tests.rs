@@ -0,0 +1,114 @@
+use std::{cell::RefCell, rc::Rc};
+
+use macroquad::prelude::*;
+
+use crate::input::{action::InputAction, bindings::{Binding, GamepadButton, InputBindings}, input_map::InputMap, source::{InputSource, MockInputSource}};
+
+/// Mock source the test keeps a hand on once the action map owns it
+struct SharedSource(Rc<RefCell<MockInputSource>>);
+
+impl InputSource for SharedSource {
+    fn is_down(&self, binding: Binding) -> bool {
+        self.0.borrow().is_down(binding)
+    }
+
+    fn is_pressed(&self, binding: Binding) -> bool {
+        self.0.borrow().is_pressed(binding)
+    }
+
+    fn last_pressed(&self) -> Option<Binding> {
+        self.0.borrow().last_pressed()
+    }
+
+    fn left_stick(&self) -> Vec2 {
+        self.0.borrow().left_stick()
+    }
+
+    fn right_stick(&self) -> Vec2 {
+        self.0.borrow().right_stick()
+    }
+}
+
+fn mock_map(bindings: InputBindings) -> (InputMap, Rc<RefCell<MockInputSource>>) {
+    let source = Rc::new(RefCell::new(MockInputSource::default()));
+    let map = InputMap::with_sources(bindings, vec![Box::new(SharedSource(source.clone()))]);
+    (map, source)
+}
+
+#[test]
+fn move_axis_is_normalized_on_diagonals() {
+    let (mut map, source) = mock_map(InputBindings::default());
+    source.borrow_mut().down = vec![Binding::Key(KeyCode::W), Binding::Key(KeyCode::D)];
+    map.update();
+    let axis = map.move_axis();
+    assert!((axis.length() - 1.).abs() < 1e-5);
+    assert!(axis.x > 0. && axis.y < 0.);
+
+    // Opposite directions cancel each other out
+    source.borrow_mut().down = vec![Binding::Key(KeyCode::A), Binding::Key(KeyCode::D)];
+    map.update();
+    assert_eq!(map.move_axis(), Vec2::ZERO);
+}
+
+#[test]
+fn move_axis_prefers_the_stick_within_its_deadzone() {
+    let (mut map, source) = mock_map(InputBindings::default());
+    source.borrow_mut().down = vec![Binding::Key(KeyCode::W)];
+    source.borrow_mut().left_stick = Vec2 { x: 0.1, y: 0. };
+    map.update();
+    // A stick resting in its deadzone lets the keys through
+    assert_eq!(map.move_axis(), Vec2 { x: 0., y: -1. });
+
+    source.borrow_mut().left_stick = Vec2 { x: 0.6, y: 0. };
+    map.update();
+    let axis = map.move_axis();
+    assert!((axis.x - 0.5).abs() < 1e-5 && axis.y == 0.);
+
+    // Pushing the stick past its range doesn't go faster
+    source.borrow_mut().left_stick = Vec2 { x: 1.5, y: 0. };
+    map.update();
+    assert_eq!(map.move_axis(), Vec2 { x: 1., y: 0. });
+}
+
+#[test]
+fn menu_step_moves_once_per_stick_push() {
+    let (mut map, source) = mock_map(InputBindings::default());
+    source.borrow_mut().left_stick = Vec2 { x: 0., y: 0.9 };
+    map.update();
+    assert_eq!(map.menu_step(), 1);
+    // Holding the stick doesn't repeat
+    map.update();
+    assert_eq!(map.menu_step(), 0);
+
+    source.borrow_mut().left_stick = Vec2::ZERO;
+    map.update();
+    assert_eq!(map.menu_step(), 0);
+    source.borrow_mut().left_stick = Vec2 { x: 0., y: -0.9 };
+    map.update();
+    assert_eq!(map.menu_step(), -1);
+
+    // Pressed buttons step on each press
+    source.borrow_mut().left_stick = Vec2::ZERO;
+    source.borrow_mut().pressed = vec![Binding::Gamepad(GamepadButton::DPadDown)];
+    map.update();
+    assert_eq!(map.menu_step(), 1);
+}
+
+#[test]
+fn set_primary_replaces_the_binding_of_the_same_device() {
+    let mut bindings = InputBindings::default();
+    bindings.set_primary(InputAction::MoveUp, Binding::Key(KeyCode::I));
+    assert_eq!(bindings.get(InputAction::MoveUp), &[Binding::Key(KeyCode::I), Binding::Key(KeyCode::Up),
+        Binding::Gamepad(GamepadButton::DPadUp)]);
+    bindings.set_primary(InputAction::MoveUp, Binding::Gamepad(GamepadButton::North));
+    assert_eq!(bindings.get(InputAction::MoveUp), &[Binding::Key(KeyCode::I), Binding::Key(KeyCode::Up),
+        Binding::Gamepad(GamepadButton::North)]);
+
+    let (mut map, source) = mock_map(bindings);
+    source.borrow_mut().down = vec![Binding::Key(KeyCode::W)];
+    map.update();
+    assert!(!map.is_down(InputAction::MoveUp));
+    source.borrow_mut().down = vec![Binding::Key(KeyCode::I)];
+    map.update();
+    assert!(map.is_down(InputAction::MoveUp));
+}
@@ -0,0 +1 @@
+pub(crate) mod passive;
\ No newline at end of file
@@ -0,0 +1,119 @@
+use serde::Deserialize;
+
+use crate::entity::stats::Stats;
+
+pub(crate) const MAX_PASSIVE_LEVEL: u8 = 5;
+pub(crate) const MAX_PASSIVES: usize = 6; // Number of passive slots of the player
+
+/// Items that don't attack but improve the player stats at each level
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub(crate) enum PassiveItem {
+    Heart,
+    Herbs,
+    PlateArmor,
+    Boots,
+    Whetstone,
+    Lens,
+    Bracer,
+    Hourglass,
+    Tome,
+    Quiver,
+    Clover,
+    Magnet,
+    Crown,
+    Brimstone,
+    Venom,
+    Fang,
+    Talisman,
+}
+
+impl PassiveItem {
+    pub(crate) const ALL: [PassiveItem; 17] = [
+        PassiveItem::Heart,
+        PassiveItem::Herbs,
+        PassiveItem::PlateArmor,
+        PassiveItem::Boots,
+        PassiveItem::Whetstone,
+        PassiveItem::Lens,
+        PassiveItem::Bracer,
+        PassiveItem::Hourglass,
+        PassiveItem::Tome,
+        PassiveItem::Quiver,
+        PassiveItem::Clover,
+        PassiveItem::Magnet,
+        PassiveItem::Crown,
+        PassiveItem::Brimstone,
+        PassiveItem::Venom,
+        PassiveItem::Fang,
+        PassiveItem::Talisman,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            PassiveItem::Heart => "Heart (max HP +20%)",
+            PassiveItem::Herbs => "Herbs (regen +0.2 HP/s)",
+            PassiveItem::PlateArmor => "Plate armor (armor +0.2)",
+            PassiveItem::Boots => "Boots (speed +10%)",
+            PassiveItem::Whetstone => "Whetstone (might +10%)",
+            PassiveItem::Lens => "Lens (area +10%)",
+            PassiveItem::Bracer => "Bracer (projectile speed +10%)",
+            PassiveItem::Hourglass => "Hourglass (cooldown -8%)",
+            PassiveItem::Tome => "Tome (duration +10%)",
+            PassiveItem::Quiver => "Quiver (amount +1)",
+            PassiveItem::Clover => "Clover (luck +10%)",
+            PassiveItem::Magnet => "Magnet (pickup range +25%)",
+            PassiveItem::Crown => "Crown (XP gain +8%)",
+            PassiveItem::Brimstone => "Brimstone (burn chance +10%)",
+            PassiveItem::Venom => "Venom (poison chance +10%)",
+            PassiveItem::Fang => "Fang (lifesteal +2%)",
+            PassiveItem::Talisman => "Talisman (resistances +5%)",
+        }
+    }
+
+    /// Short name fitting in a HUD slot
+    pub(crate) fn abbreviation(&self) -> &'static str {
+        match self {
+            PassiveItem::Heart => "HP",
+            PassiveItem::Herbs => "RG",
+            PassiveItem::PlateArmor => "AR",
+            PassiveItem::Boots => "SP",
+            PassiveItem::Whetstone => "MT",
+            PassiveItem::Lens => "AE",
+            PassiveItem::Bracer => "PS",
+            PassiveItem::Hourglass => "CD",
+            PassiveItem::Tome => "DU",
+            PassiveItem::Quiver => "AM",
+            PassiveItem::Clover => "LK",
+            PassiveItem::Magnet => "MG",
+            PassiveItem::Crown => "XP",
+            PassiveItem::Brimstone => "BR",
+            PassiveItem::Venom => "VN",
+            PassiveItem::Fang => "FG",
+            PassiveItem::Talisman => "TL",
+        }
+    }
+
+    /// Apply the bonus of the item at the given level on top of the stats
+    pub(crate) fn apply(&self, stats: &mut Stats, level: u8) {
+        let level = level as f32;
+        match self {
+            PassiveItem::Heart => stats.max_hp *= 1. + 0.2 * level,
+            PassiveItem::Herbs => stats.regen += 0.2 * level,
+            PassiveItem::PlateArmor => stats.armor += 0.2 * level,
+            PassiveItem::Boots => stats.move_speed += 0.1 * level,
+            PassiveItem::Whetstone => stats.might += 0.1 * level,
+            PassiveItem::Lens => stats.area += 0.1 * level,
+            PassiveItem::Bracer => stats.projectile_speed += 0.1 * level,
+            PassiveItem::Hourglass => stats.cooldown_reduction += 0.08 * level,
+            PassiveItem::Tome => stats.duration += 0.1 * level,
+            PassiveItem::Quiver => stats.amount += level as u8,
+            PassiveItem::Clover => stats.luck += 0.1 * level,
+            PassiveItem::Magnet => stats.magnet_radius *= 1. + 0.25 * level,
+            PassiveItem::Crown => stats.xp_gain += 0.08 * level,
+            PassiveItem::Brimstone => stats.burn_chance += 0.1 * level,
+            PassiveItem::Venom => stats.poison_chance += 0.1 * level,
+            PassiveItem::Fang => stats.lifesteal += 0.02 * level,
+            PassiveItem::Talisman => stats.resistances.raise(0.05 * level),
+        }
+    }
+}
@@ -1,11 +1,26 @@
-use std::{panic};
-
 use macroquad::prelude::*;
 
+use crate::asset_manager::AssetManager;
+use crate::data::Definitions;
+use crate::entity::character_definition::ROSTER_PATH;
+use crate::entity::ennemy_definition::ENNEMIES_PATH;
+use crate::entity::prop_definition::PROPS_PATH;
+use crate::entity::elite::ELITES_PATH;
 use crate::game::Game;
+use crate::hot_reload::hot_reload;
+use crate::input::action::InputAction;
+use crate::input::input_map::InputMap;
+use crate::particles::emitter_definition::PARTICLES_PATH;
+use crate::settings::Settings;
 use crate::survivor_ui::window::Action;
+use crate::survivor_ui::loading_screen::{draw_loading_error, draw_loading_screen};
+use crate::survivor_ui::skin::{UI_FONT_PATH, menu_skin};
+use crate::survivor_ui::character_select_window::CharacterSelectWindow;
 use crate::survivor_ui::main_menu_window::MainMenuWindow;
+use crate::survivor_ui::options_window::OptionsWindow;
 use crate::survivor_ui::pause_window::PauseWindow;
+use crate::survivor_ui::toast::Toasts;
+use crate::weapons::weapon_definition::WEAPONS_PATH;
 
 mod weapons;
 mod collision;
@@ -14,115 +29,146 @@ mod entity;
 mod survivor_rng;
 mod draw_utils;
 mod survivor_ui;
+mod input;
+mod settings;
+mod progression;
+mod items;
+mod data;
+mod asset_manager;
+mod atlas;
+mod hot_reload;
+mod damage;
+mod feedback;
+mod particles;
+mod camera;
+mod render_queue;
+mod telegraph;
 
 enum GameState {
     Game,
     Pause,
-    GameOver,
+    GameOver { score: i16 },
     MainMenu,
+    CharacterSelect,
+    // Options can be opened from the main menu or the pause menu
+    Options { from_pause: bool },
 }
 
 
 #[macroquad::main("BasicShapes")]
 async fn main() {
     
-    let sword_texture_result = load_texture("assets/sword.png").await;
-    let sword_texture: Texture2D;
-    match sword_texture_result {
-        Ok(texture) => {
-            sword_texture = texture;
-        }
-        Err(error) => panic!("{error}"),
-    }
-    
-    let player_idle_texture_result = load_texture("assets/player_idle.png").await;
-    let player_idle_texture: Texture2D;
-    match player_idle_texture_result {
-        Ok(texture) => {
-            player_idle_texture = texture;
-        }
-        Err(error) => panic!("{error}"),
-    }
-    
-    let player_walking_texture_result = load_texture("assets/player_walk.png").await;
-    let player_walking_texture: Texture2D;
-    match player_walking_texture_result {
-        Ok(texture) => {
-            player_walking_texture = texture;
+    // Invalid definitions are shown until they are fixed, instead of closing the game
+    let mut definitions = loop {
+        match Definitions::load().await {
+            Ok(definitions) => break definitions,
+            Err(error) => {
+                println!("{error}");
+                loop {
+                    draw_loading_error(&error);
+                    next_frame().await;
+                    if !get_keys_pressed().is_empty() {
+                        break;
+                    }
+                }
+            }
         }
-        Err(error) => panic!("{error}"),
-    }
+    };
 
-    let dagger_texture_result = load_texture("assets/dagger.png").await;
-    let dagger_texture: Texture2D;
-    match dagger_texture_result {
-        Ok(texture) => {
-            dagger_texture = texture;
-        }
-        Err(error) => panic!("{error}"),
+    let mut assets = AssetManager::new();
+    definitions.queue_textures(&mut assets);
+    for path in [ROSTER_PATH, WEAPONS_PATH, ENNEMIES_PATH, PARTICLES_PATH, PROPS_PATH, ELITES_PATH] {
+        assets.watch(path);
     }
+    let ui_font = assets.queue_font(UI_FONT_PATH);
 
-    let orc_texture_result = load_texture("assets/orc.png").await;
-    let orc_texture: Texture2D;
-    match orc_texture_result {
-        Ok(texture) => {
-            orc_texture = texture;
-        }
-        Err(error) => panic!("{error}"),
+    while !assets.is_loaded() {
+        draw_loading_screen(assets.progress());
+        next_frame().await;
+        assets.load_next().await;
     }
+    let menu_skin = assets.add_skin(ui_font, menu_skin);
 
-    let grass_texture_result = load_texture("assets/grass_zoom.png").await;
-    let grass_texture: Texture2D;
-    match grass_texture_result {
-        Ok(texture) => {
-            grass_texture = texture;
-        }
-        Err(error) => panic!("{error}"),
-    }
+    let mut main_menu_window = MainMenuWindow::new(menu_skin);
+    let mut pause_window = PauseWindow::new(menu_skin);
+    let mut options_window = OptionsWindow::new(menu_skin);
+    let mut character_select_window = CharacterSelectWindow::new(menu_skin);
 
-    let main_menu_window = MainMenuWindow::new().await;
-    let pause_window = PauseWindow::new().await;
+    let mut settings = Settings::load();
+    let mut input_map = InputMap::new(settings.bindings.clone());
 
     set_default_filter_mode(FilterMode::Nearest);
     
-    let mut game = Game::new(&sword_texture, &player_idle_texture, &player_walking_texture, 
-        &dagger_texture, &orc_texture, &grass_texture);
+    let mut toasts = Toasts::new();
+    let mut selected_character = 0;
+    let mut game = new_game(&definitions, selected_character, &settings, &mut assets);
     let mut game_state = GameState::MainMenu;
 
     println!("Screen width: {}, Screen height: {}", screen_width(), screen_height());
     
     loop {
+        input_map.update();
         match game_state {
             GameState::MainMenu => {
-                game_state = state_main_menu(&main_menu_window);
+                game_state = state_main_menu(&mut main_menu_window, &input_map, &assets);
+            }
+            GameState::CharacterSelect => {
+                match character_select_window.draw(&input_map, &definitions.roster, &assets) {
+                    Some(Action::SelectCharacter(index)) => {
+                        selected_character = index;
+                        game = new_game(&definitions, selected_character, &settings, &mut assets);
+                        game_state = GameState::Game;
+                    }
+                    Some(Action::Back) => game_state = GameState::MainMenu,
+                    _ => (),
+                }
             }
             GameState::Game => {
-                game_state = state_game(&mut game);
+                game_state = state_game(&mut game, &input_map, &mut settings, &assets);
             }
             GameState::Pause => {
-                game_state = state_pause(&pause_window);
+                game_state = state_pause(&mut pause_window, &input_map, &assets);
+            }
+            GameState::Options { from_pause } => {
+                game_state = state_options(&mut options_window, &mut input_map, &mut settings, from_pause, &assets);
             }
-            GameState::GameOver => {
-                draw_text("Game Over! Press any key to restart.", 10., 10., 20., WHITE);
+            GameState::GameOver { score } => {
+                draw_text(&format!("Game Over! Score : {score}. Press any key to restart."), 10., 10., 20., WHITE);
+                for (i, line) in game.damage_report().iter().enumerate() {
+                    draw_text(line, 10., 40. + i as f32 * 20., 20., LIGHTGRAY);
+                }
                 if !get_keys_pressed().is_empty() {
-                    game = Game::new(&sword_texture, &player_idle_texture, &player_walking_texture,
-                        &dagger_texture, &orc_texture, &grass_texture);
+                    // Restart with the same character
+                    game = new_game(&definitions, selected_character, &settings, &mut assets);
                     game_state = GameState::Game;
                 }
             }
         }
-        draw_fps();
+        if cfg!(debug_assertions) {
+            hot_reload(&mut assets, &mut definitions, &mut game, &mut selected_character, &mut toasts).await;
+            toasts.draw();
+            draw_fps();
+        }
         next_frame().await;
     }
 }
 
-fn state_game(game: &mut Game) -> GameState {
-    if is_key_pressed(KeyCode::Escape) {
+fn new_game(definitions: &Definitions, character: usize, settings: &Settings, assets: &mut AssetManager) -> Game {
+    let textures = definitions.textures(character, assets);
+    Game::new(definitions, character, textures, settings)
+}
+
+fn state_game(game: &mut Game, input_map: &InputMap, settings: &mut Settings, assets: &AssetManager) -> GameState {
+    if input_map.is_pressed(InputAction::Pause) {
         return GameState::Pause;
     }
-    let game_data = game.update();
+    if input_map.is_pressed(InputAction::ToggleMinimap) {
+        settings.minimap = !settings.minimap;
+        settings.save();
+    }
+    let game_data = game.update(input_map, settings, assets);
     if game_data.is_game_over {
-        GameState::GameOver
+        GameState::GameOver { score: game_data.score }
     } else {
         GameState::Game
     }
@@ -138,13 +184,13 @@ fn state_game(game: &mut Game) -> GameState {
     // }
 }
 
-fn state_main_menu(main_menu_window: &MainMenuWindow) -> GameState {
-    let action = main_menu_window.draw();
+fn state_main_menu(main_menu_window: &mut MainMenuWindow, input_map: &InputMap, assets: &AssetManager) -> GameState {
+    let action = main_menu_window.draw(input_map, assets);
     if let Some(action) = action {
         match action {
-            Action::Play => GameState::Game,
-            Action::Options => GameState::MainMenu, // In a real app, this would go to options screen
-            Action::Quit => GameState::GameOver,
+            Action::Play => GameState::CharacterSelect,
+            Action::Options => GameState::Options { from_pause: false },
+            Action::Quit => GameState::GameOver { score: 0 },
             _ => GameState::MainMenu, // This case should not happen, but we handle it just in case
         }
     } else {
@@ -152,12 +198,15 @@ fn state_main_menu(main_menu_window: &MainMenuWindow) -> GameState {
     }
 }
 
-fn state_pause(pause_window: &PauseWindow) -> GameState {
-    let action = pause_window.draw();
+fn state_pause(pause_window: &mut PauseWindow, input_map: &InputMap, assets: &AssetManager) -> GameState {
+    if input_map.is_pressed(InputAction::Pause) {
+        return GameState::Game;
+    }
+    let action = pause_window.draw(input_map, assets);
     if let Some(action) = action {
         match action {
             Action::Resume => GameState::Game,
-            Action::Options => GameState::MainMenu, // In a real app, this would go to options screen
+            Action::Options => GameState::Options { from_pause: true },
             Action::QuitToMainMenu => GameState::MainMenu,
             _ => GameState::Pause, // This case should not happen, but we handle it just in case
         }
@@ -167,3 +216,15 @@ fn state_pause(pause_window: &PauseWindow) -> GameState {
     
 }
 
+fn state_options(options_window: &mut OptionsWindow, input_map: &mut InputMap, settings: &mut Settings,
+    from_pause: bool, assets: &AssetManager) -> GameState {
+    let action = options_window.draw(input_map, settings, assets);
+    match action {
+        Some(Action::Back) => {
+            settings.bindings = input_map.bindings.clone();
+            settings.save();
+            if from_pause { GameState::Pause } else { GameState::MainMenu }
+        }
+        _ => GameState::Options { from_pause },
+    }
+}
@@ -0,0 +1,99 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::data::{ensure, ensure_non_negative, ensure_positive, load_ron};
+
+pub(crate) const PARTICLES_PATH: &str = "assets/particles.ron";
+
+/// A kind of particle effect, as described in `assets/particles.ron`
+#[derive(Clone, Deserialize)]
+pub(crate) struct EmitterDefinition {
+    pub(crate) name: String,
+    pub(crate) emission: Emission,
+    /// Seconds, picked between the two values
+    pub(crate) lifetime: (f32, f32),
+    /// Pixels per second, picked between the two values
+    pub(crate) speed: (f32, f32),
+    /// Angle in radians around the emission direction in which particles leave, a full turn being 6.28
+    #[serde(default)]
+    pub(crate) spread: f32,
+    /// Acceleration in pixels per second squared
+    #[serde(default)]
+    pub(crate) gravity: Vec2,
+    /// Colors the particles go through during their life, evenly spaced
+    pub(crate) colors: Vec<[f32; 4]>,
+    /// Sizes in pixels the particles go through during their life, evenly spaced
+    pub(crate) sizes: Vec<f32>,
+    #[serde(default)]
+    pub(crate) shape: ParticleShape,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub(crate) enum Emission {
+    /// Number of particles spawned at once
+    Burst(u16),
+    /// Particles spawned each second, while the effect is emitted every frame
+    Continuous(f32),
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub(crate) enum ParticleShape {
+    #[default]
+    Circle,
+    Square,
+    /// Image of the atlas, tinted by the colors
+    Sprite(String),
+}
+
+impl EmitterDefinition {
+    /// Color at a point of the life of a particle, from 0 to 1
+    pub(crate) fn color(&self, life: f32) -> Color {
+        let [r, g, b, a] = sample(&self.colors, life, |from, to, t| {
+            std::array::from_fn(|channel| from[channel] + (to[channel] - from[channel]) * t)
+        });
+        Color::new(r, g, b, a)
+    }
+
+    /// Size at a point of the life of a particle, from 0 to 1
+    pub(crate) fn size(&self, life: f32) -> f32 {
+        sample(&self.sizes, life, |from, to, t| from + (to - from) * t)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let name = &self.name;
+        match self.emission {
+            Emission::Burst(count) => ensure(count > 0, &format!("{name}.emission"), "must burst at least 1 particle")?,
+            Emission::Continuous(rate) => ensure_positive(rate, &format!("{name}.emission"))?,
+        }
+        ensure_positive(self.lifetime.0, &format!("{name}.lifetime"))?;
+        ensure(self.lifetime.0 <= self.lifetime.1, &format!("{name}.lifetime"), "must be (min, max)")?;
+        ensure_non_negative(self.speed.0, &format!("{name}.speed"))?;
+        ensure(self.speed.0 <= self.speed.1, &format!("{name}.speed"), "must be (min, max)")?;
+        ensure_non_negative(self.spread, &format!("{name}.spread"))?;
+        ensure(!self.colors.is_empty(), &format!("{name}.colors"), "can't be empty")?;
+        ensure(!self.sizes.is_empty(), &format!("{name}.sizes"), "can't be empty")?;
+        for size in &self.sizes {
+            ensure_non_negative(*size, &format!("{name}.sizes"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Interpolate between the evenly spaced values at `t`, from 0 to 1
+fn sample<T: Copy>(values: &[T], t: f32, lerp: impl Fn(T, T, f32) -> T) -> T {
+    if values.len() == 1 {
+        return values[0];
+    }
+    let position = t.clamp(0., 1.) * (values.len() - 1) as f32;
+    let index = (position as usize).min(values.len() - 2);
+    lerp(values[index], values[index + 1], position - index as f32)
+}
+
+/// Load every particle effect, the error naming the faulty field when they are invalid
+pub(crate) async fn load_emitter_definitions() -> Result<Vec<EmitterDefinition>, String> {
+    let definitions: Vec<EmitterDefinition> = load_ron(PARTICLES_PATH).await?;
+    for definition in &definitions {
+        definition.validate().map_err(|error| format!("Invalid {PARTICLES_PATH}: {error}"))?;
+    }
+    Ok(definitions)
+}
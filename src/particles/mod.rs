@@ -0,0 +1,2 @@
+pub mod emitter_definition;
+pub mod particle_system;
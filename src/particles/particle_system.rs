@@ -0,0 +1,129 @@
+use ::rand::{Rng, rng, rngs::ThreadRng};
+use macroquad::prelude::*;
+
+use crate::{asset_manager::{AssetManager, SpriteHandle}, draw_utils::is_on_screen, particles::emitter_definition::{Emission, EmitterDefinition, ParticleShape}};
+
+struct Particle {
+    world_position: Vec2,
+    /// Pixels per second
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    /// Index of the emitter definition
+    emitter: usize,
+}
+
+/// Particles of every effect, kept in a pool allocated for the particle budget so that
+/// emitting doesn't allocate. Emitting past the budget does nothing.
+pub(crate) struct ParticleSystem {
+    particles: Vec<Particle>,
+    definitions: Vec<EmitterDefinition>,
+    /// Sprites of the emitters drawing one, in the order of the definitions
+    sprites: Vec<Option<SpriteHandle>>,
+    budget: usize,
+    rng: ThreadRng,
+}
+
+impl ParticleSystem {
+    pub(crate) fn new(definitions: &[EmitterDefinition], sprites: Vec<Option<SpriteHandle>>, budget: usize) -> Self {
+        Self {
+            particles: Vec::with_capacity(budget),
+            definitions: definitions.to_vec(),
+            sprites,
+            budget,
+            rng: rng(),
+        }
+    }
+
+    /// Use reloaded definitions, removing the current particles
+    pub(crate) fn apply_definitions(&mut self, definitions: &[EmitterDefinition], sprites: Vec<Option<SpriteHandle>>) {
+        self.particles.clear();
+        self.definitions = definitions.to_vec();
+        self.sprites = sprites;
+    }
+
+    /// Spawn the particles of an effect: all of them for a burst, the ones due during the frame for
+    /// a continuous effect, which is then emitted every frame. Particles leave around `direction`,
+    /// every way when it is zero, from a random point of a disc of `radius`.
+    /// An effect missing from the definitions isn't drawn.
+    pub(crate) fn emit(&mut self, name: &str, world_position: Vec2, direction: Vec2, radius: f32) {
+        let Some(emitter) = self.definitions.iter().position(|definition| definition.name == name) else {
+            return;
+        };
+        let definition = &self.definitions[emitter];
+        let count = match definition.emission {
+            Emission::Burst(count) => count as usize,
+            Emission::Continuous(rate) => {
+                // The fraction of a particle left is spawned or not at random, so low rates still emit
+                let expected = rate * get_frame_time();
+                expected as usize + (self.rng.random::<f32>() < expected.fract()) as usize
+            }
+        };
+        let count = count.min(self.budget.saturating_sub(self.particles.len()));
+        for _ in 0..count {
+            let angle = if direction == Vec2::ZERO {
+                self.rng.random_range(0.0..std::f32::consts::TAU)
+            } else {
+                direction.y.atan2(direction.x) + (self.rng.random::<f32>() - 0.5) * definition.spread
+            };
+            let offset = Vec2::from_angle(self.rng.random_range(0.0..std::f32::consts::TAU))
+                * radius * self.rng.random::<f32>().sqrt();
+            let speed = random_between(&mut self.rng, definition.speed);
+            self.particles.push(Particle {
+                world_position: world_position + offset,
+                velocity: Vec2::from_angle(angle) * speed,
+                age: 0.,
+                lifetime: random_between(&mut self.rng, definition.lifetime),
+                emitter,
+            });
+        }
+    }
+
+    /// Move the particles, and resize the pool when the budget changed
+    pub(crate) fn update(&mut self, budget: usize) {
+        if budget != self.budget {
+            self.budget = budget;
+            self.particles.truncate(budget);
+            self.particles.reserve_exact(budget - self.particles.len());
+        }
+        let dt = get_frame_time();
+        for particle in self.particles.iter_mut() {
+            particle.velocity += self.definitions[particle.emitter].gravity * dt;
+            particle.world_position += particle.velocity * dt;
+            particle.age += dt;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2, assets: &AssetManager) {
+        for particle in &self.particles {
+            let screen_position = particle.world_position - screen_origin_position;
+            if !is_on_screen(screen_position) {
+                continue;
+            }
+            let definition = &self.definitions[particle.emitter];
+            let life = particle.age / particle.lifetime;
+            let color = definition.color(life);
+            let size = definition.size(life);
+            match (&definition.shape, self.sprites[particle.emitter]) {
+                (ParticleShape::Sprite(_), Some(handle)) => {
+                    let sprite = assets.sprite(handle);
+                    draw_texture_ex(sprite.texture, screen_position.x - size / 2., screen_position.y - size / 2.,
+                        color, DrawTextureParams {
+                            source: Some(sprite.region_for_width(size)),
+                            dest_size: Some(Vec2 { x: size, y: size }),
+                            ..Default::default()
+                        });
+                }
+                (ParticleShape::Square, _) => {
+                    draw_rectangle(screen_position.x - size / 2., screen_position.y - size / 2., size, size, color);
+                }
+                _ => draw_circle(screen_position.x, screen_position.y, size / 2., color),
+            }
+        }
+    }
+}
+
+fn random_between(rng: &mut ThreadRng, (min, max): (f32, f32)) -> f32 {
+    if min >= max { min } else { rng.random_range(min..max) }
+}
@@ -0,0 +1,62 @@
+use crate::{items::passive::PassiveItem, weapons::weapon_slot::WeaponKind};
+
+const BASE_LEVEL_XP: f32 = 5.; // Experience needed to reach level 2
+const LEVEL_XP_INCREMENT: f32 = 3.; // Extra experience needed for each following level
+
+/// Upgrades rolled when the player levels up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Upgrade {
+    DashCharge,
+    DashCooldown,
+    /// Get a new passive item or level up an owned one
+    Passive(PassiveItem),
+    /// Get a new weapon or level up an owned one
+    Weapon(WeaponKind),
+}
+
+impl Upgrade {
+    pub(crate) fn all() -> Vec<Upgrade> {
+        let mut upgrades = vec![Upgrade::DashCharge, Upgrade::DashCooldown];
+        upgrades.extend(PassiveItem::ALL.iter().map(|item| Upgrade::Passive(*item)));
+        upgrades.extend(WeaponKind::ALL.iter().map(|kind| Upgrade::Weapon(*kind)));
+        upgrades
+    }
+
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Upgrade::DashCharge => "Dash charge +1".to_string(),
+            Upgrade::DashCooldown => "Dash cooldown -20%".to_string(),
+            Upgrade::Passive(item) => item.label().to_string(),
+            Upgrade::Weapon(kind) => format!("Weapon : {}", kind.label()),
+        }
+    }
+}
+
+/// Experience and level of the player during a run
+pub(crate) struct Progression {
+    pub(crate) level: u16,
+    pub(crate) xp: f32,
+}
+
+impl Progression {
+    pub(crate) fn new() -> Self {
+        Self { level: 1, xp: 0. }
+    }
+
+    /// Experience needed to go from the current level to the next one
+    pub(crate) fn xp_to_next_level(&self) -> f32 {
+        BASE_LEVEL_XP + (self.level - 1) as f32 * LEVEL_XP_INCREMENT
+    }
+
+    /// Add experience and return the number of levels gained
+    pub(crate) fn add_xp(&mut self, xp: f32) -> u16 {
+        self.xp += xp;
+        let mut levels_gained = 0;
+        while self.xp >= self.xp_to_next_level() {
+            self.xp -= self.xp_to_next_level();
+            self.level += 1;
+            levels_gained += 1;
+        }
+        levels_gained
+    }
+}
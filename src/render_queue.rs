@@ -0,0 +1,47 @@
+/// Layers of the frame, drawn in this order
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Layer {
+    /// The background
+    Ground,
+    /// Things lying on the ground: corpses, gems, the aura
+    Decals,
+    Shadows,
+    /// Characters, sorted by the position of their feet so the lower ones are in front
+    Actors,
+    /// Weapons flying above the characters
+    Projectiles,
+    Effects,
+    Hud,
+}
+
+struct DrawCommand<'a> {
+    layer: Layer,
+    depth: f32,
+    draw: Box<dyn FnOnce() + 'a>,
+}
+
+/// Draws queued during the frame, then drawn sorted by layer and depth,
+/// whatever the order the entities are updated and queued in
+pub(crate) struct RenderQueue<'a> {
+    commands: Vec<DrawCommand<'a>>,
+}
+
+impl<'a> RenderQueue<'a> {
+    pub(crate) fn new() -> Self {
+        Self { commands: Vec::new() }
+    }
+
+    /// Queue a draw. Draws of a layer are sorted by increasing depth, e.g. the y position of the feet
+    /// for actors, and draws with the same depth keep the order they were queued in.
+    pub(crate) fn push(&mut self, layer: Layer, depth: f32, draw: impl FnOnce() + 'a) {
+        self.commands.push(DrawCommand { layer, depth, draw: Box::new(draw) });
+    }
+
+    pub(crate) fn draw(mut self) {
+        // The sort is stable, keeping the queued order of equal draws
+        self.commands.sort_by(|a, b| a.layer.cmp(&b.layer).then(a.depth.total_cmp(&b.depth)));
+        for command in self.commands {
+            (command.draw)();
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::fs;
+
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::input::bindings::InputBindings;
+
+const SETTINGS_PATH: &str = "settings.ron";
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 3.;
+
+/// Player settings, persisted between runs in `settings.ron`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Settings {
+    pub(crate) bindings: InputBindings,
+    /// Leave translucent copies of the player behind while dashing
+    pub(crate) dash_afterimages: bool,
+    /// Damage dealt and taken rising above the characters
+    pub(crate) damage_numbers: bool,
+    /// Struck characters drawn in white for a moment
+    pub(crate) hit_flash: bool,
+    /// Freeze the game for a moment on critical and big hits
+    pub(crate) hit_stop: bool,
+    /// Maximum number of particles alive at once, 0 turns them off
+    pub(crate) particle_budget: usize,
+    /// Turn off the screen shake and the slow motion
+    pub(crate) reduced_motion: bool,
+    /// Size of the HUD, 1 being its default size
+    pub(crate) ui_scale: f32,
+    /// Show the map of the surroundings in the lower right corner
+    pub(crate) minimap: bool,
+    /// Side of the minimap in pixels, before the UI scale
+    pub(crate) minimap_size: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            bindings: InputBindings::default(),
+            dash_afterimages: true,
+            damage_numbers: true,
+            hit_flash: true,
+            hit_stop: true,
+            particle_budget: 1000,
+            reduced_motion: false,
+            ui_scale: 1.,
+            minimap: true,
+            minimap_size: 160.,
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings file, falling back to the defaults if it is missing or invalid
+    pub(crate) fn load() -> Self {
+        let Ok(content) = fs::read_to_string(SETTINGS_PATH) else {
+            return Settings::default();
+        };
+        match ron::from_str::<Settings>(&content) {
+            Ok(mut settings) => {
+                settings.bindings.fill_missing();
+                settings
+            }
+            Err(error) => {
+                println!("Invalid {SETTINGS_PATH}, using default settings: {error}");
+                Settings::default()
+            }
+        }
+    }
+
+    /// UI scale kept within sizes the HUD can be laid out at
+    pub(crate) fn ui_scale(&self) -> f32 {
+        self.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+    }
+
+    pub(crate) fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|content| fs::write(SETTINGS_PATH, content).map_err(|error| error.to_string()));
+        if let Err(error) = result {
+            println!("Could not save {SETTINGS_PATH}: {error}");
+        }
+    }
+}
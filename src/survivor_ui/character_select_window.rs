@@ -0,0 +1,73 @@
+use macroquad::prelude::*;
+
+use macroquad::ui::{hash, root_ui, widgets};
+
+use crate::asset_manager::{AssetManager, SkinHandle};
+use crate::entity::character_definition::CharacterDefinition;
+use crate::input::input_map::InputMap;
+use crate::survivor_ui::window::{Action, Window};
+
+const WINDOW_WIDTH: f32 = 600.;
+const WINDOW_HEIGHT: f32 = 500.;
+const WINDOW_POSITION: Vec2 = Vec2::new(800. / 2. - WINDOW_WIDTH / 2., 600. / 2. - WINDOW_HEIGHT / 2.);
+const ROW_HEIGHT: f32 = 60.; // Vertical space between two character buttons
+const LINE_HEIGHT: f32 = 35.; // Vertical space between two lines of the focused character details
+
+pub(crate) struct CharacterSelectWindow {
+    pub(crate) window: Window,
+}
+
+impl CharacterSelectWindow {
+    pub(crate) fn new(skin: SkinHandle) -> Self {
+        Self {
+            window: Window::new(WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_POSITION, skin),
+        }
+    }
+
+    pub(crate) fn draw(&mut self, input_map: &InputMap, roster: &[CharacterDefinition],
+        assets: &AssetManager) -> Option<Action> {
+        // One button per character, then the "Back" button
+        let confirmed = self.window.navigate(input_map, roster.len() + 1);
+        let focused = self.window.focused;
+
+        root_ui().push_skin(assets.skin(self.window.skin));
+
+        root_ui().same_line(0.);
+
+        let mut action: Option<Action> = confirmed.map(|i| {
+            if i < roster.len() { Action::SelectCharacter(i) } else { Action::Back }
+        });
+        // Details of the focused character, or of the first one when "Back" is focused
+        let shown = &roster[focused.min(roster.len() - 1)];
+
+        root_ui().window(
+            hash!(), self.window.position, vec2(self.window.width,
+                self.window.height),
+                |ui| {
+            for (i, character) in roster.iter().enumerate() {
+                if widgets::Button::new(character.name.as_str())
+                .position(vec2(20.0, 15.0 + i as f32 * ROW_HEIGHT))
+                .selected(i == focused)
+                .ui(ui) {
+                    action = Some(Action::SelectCharacter(i));
+                }
+            }
+            if widgets::Button::new("Back")
+            .position(vec2(20.0, WINDOW_HEIGHT - 130.0))
+            .selected(focused == roster.len())
+            .ui(ui) {
+                action = Some(Action::Back);
+            }
+
+            // Details are listed under the character buttons
+            let details_y = 35.0 + roster.len() as f32 * ROW_HEIGHT;
+            ui.label(vec2(20.0, details_y), &shown.description);
+            ui.label(vec2(20.0, details_y + LINE_HEIGHT),
+                &format!("Weapon : {}", shown.starting_weapon.label()));
+            ui.label(vec2(20.0, details_y + 2. * LINE_HEIGHT),
+                &format!("HP : {}  Speed : {}", shown.base_stats.max_hp, shown.base_stats.move_speed));
+            ui.label(vec2(20.0, details_y + 3. * LINE_HEIGHT), &shown.passive.description);
+        });
+        action
+    }
+}
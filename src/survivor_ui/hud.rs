@@ -0,0 +1,278 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use macroquad::prelude::*;
+
+use crate::{asset_manager::{AssetManager, FontHandle}, entity::{dash::Dash, player::Player}, items::passive::{MAX_PASSIVE_LEVEL, MAX_PASSIVES, PassiveItem}, progression::{Progression, Upgrade}, weapons::weapon_slot::{MAX_WEAPONS, WeaponSlot}};
+
+// Sizes before scaling
+const MARGIN: f32 = 10.; // Space between the HUD and the edges of the screen
+const FONT_SIZE: f32 = 16.;
+const TIMER_FONT_SIZE: f32 = 28.;
+const XP_BAR_HEIGHT: f32 = 8.;
+const HEALTH_BAR_WIDTH: f32 = 160.;
+const HEALTH_BAR_HEIGHT: f32 = 16.;
+const OVERHEAD_BAR_WIDTH: f32 = 32.;
+const OVERHEAD_BAR_HEIGHT: f32 = 4.;
+const OVERHEAD_BAR_OFFSET: f32 = 4.; // Space between the head of the player and its bar
+const STATUS_OFFSET: f32 = 6.; // Space between the overhead bar and the status effects of the player
+const SLOT_SIZE: f32 = 28.;
+const SLOT_GAP: f32 = 4.;
+const SLOT_PADDING: f32 = 4.; // Space between the border of a slot and its icon
+const PIP_SIZE: f32 = 3.;
+const PIP_GAP: f32 = 1.;
+const DASH_BAR_WIDTH: f32 = 60.;
+const DASH_BAR_HEIGHT: f32 = 6.;
+const BOSS_BAR_WIDTH: f32 = 400.;
+const BOSS_BAR_HEIGHT: f32 = 12.;
+const COOLDOWN_SEGMENTS: f32 = 32.; // Triangles of a full cooldown sweep
+const BAR_BACKGROUND: Color = Color::new(0., 0., 0., 0.6);
+const HEALTH_COLOR: Color = Color::new(0.85, 0.15, 0.15, 1.);
+const XP_COLOR: Color = Color::new(0.3, 0.6, 1., 1.);
+const BOSS_COLOR: Color = Color::new(0.6, 0.1, 0.6, 1.);
+const SLOT_BACKGROUND: Color = Color::new(0., 0., 0., 0.5);
+const SLOT_BORDER: Color = Color::new(0.8, 0.8, 0.8, 0.8);
+const EVOLVED_SLOT_BORDER: Color = Color::new(1., 0.8, 0.1, 1.);
+const EMPTY_SLOT_BORDER: Color = Color::new(0.4, 0.4, 0.4, 0.5);
+const COOLDOWN_COLOR: Color = Color::new(0., 0., 0., 0.6);
+const PIP_COLOR: Color = Color::new(1., 0.8, 0.1, 1.);
+const EMPTY_PIP_COLOR: Color = Color::new(0.3, 0.3, 0.3, 1.);
+
+/// Health bar of a boss, at the bottom of the screen
+pub(crate) struct BossBar<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) hp_ratio: f32,
+}
+
+/// State of the run shown by the HUD
+pub(crate) struct HudInfo<'a> {
+    pub(crate) player: &'a Player,
+    pub(crate) progression: &'a Progression,
+    /// Seconds survived
+    pub(crate) time: f32,
+    pub(crate) kills: i16,
+    pub(crate) gold: u32,
+    pub(crate) last_upgrade: Option<Upgrade>,
+    pub(crate) bosses: Vec<BossBar<'a>>,
+}
+
+/// Heads-up display drawn over the game: health and experience bars, survival timer, kills and gold,
+/// weapon and passive slots, dash charges and boss health bars. Everything is scaled by the UI scale.
+pub(crate) struct Hud<'a> {
+    assets: &'a AssetManager,
+    font: Option<&'a Font>,
+    scale: f32,
+}
+
+impl<'a> Hud<'a> {
+    pub(crate) fn new(assets: &'a AssetManager, font: FontHandle, scale: f32) -> Self {
+        Self {
+            assets,
+            font: assets.font(font),
+            scale,
+        }
+    }
+
+    pub(crate) fn draw(&self, info: &HudInfo, screen_origin_position: Vec2) {
+        let player = info.player;
+        self.draw_overhead_bar(player, screen_origin_position);
+
+        let xp_ratio = info.progression.xp / info.progression.xp_to_next_level();
+        self.draw_bar(Rect::new(0., 0., screen_width(), self.px(XP_BAR_HEIGHT)), xp_ratio, XP_COLOR);
+
+        let health_bar = Rect::new(self.px(MARGIN), self.px(XP_BAR_HEIGHT + MARGIN),
+            self.px(HEALTH_BAR_WIDTH), self.px(HEALTH_BAR_HEIGHT));
+        self.draw_bar(health_bar, player.character.hp / player.stats.max_hp, HEALTH_COLOR);
+        let hp = format!("{:.0}/{:.0}", player.character.hp.max(0.).ceil(), player.stats.max_hp);
+        self.draw_text_centered(&hp, health_bar.center(), FONT_SIZE, WHITE);
+        let level = format!("LV {}", info.progression.level);
+        self.draw_text(&level, health_bar.right() + self.px(MARGIN), self.baseline(health_bar), FONT_SIZE, WHITE);
+
+        let seconds = info.time as u32;
+        let timer = format!("{:02}:{:02}", seconds / 60, seconds % 60);
+        let timer_y = self.px(XP_BAR_HEIGHT + MARGIN) + self.text_height(&timer, TIMER_FONT_SIZE);
+        self.draw_text(&timer, (screen_width() - self.text_width(&timer, TIMER_FONT_SIZE)) / 2., timer_y,
+            TIMER_FONT_SIZE, WHITE);
+
+        self.draw_counters(info.kills, info.gold);
+        let slots_bottom = self.draw_slots(player, health_bar.bottom() + self.px(MARGIN));
+        if let Some(upgrade) = info.last_upgrade {
+            let y = slots_bottom + self.px(MARGIN) + self.text_height("L", FONT_SIZE);
+            self.draw_text(&format!("Last upgrade : {}", upgrade.label()), self.px(MARGIN), y, FONT_SIZE, WHITE);
+        }
+        self.draw_dash(&player.dash);
+        self.draw_boss_bars(&info.bosses);
+    }
+
+    /// Small health bar following the player, above its head, under its status effects
+    fn draw_overhead_bar(&self, player: &Player, screen_origin_position: Vec2) {
+        let (width, height) = (self.px(OVERHEAD_BAR_WIDTH), self.px(OVERHEAD_BAR_HEIGHT));
+        let x = player.character.world_position.x - screen_origin_position.x - width / 2.;
+        let y = player.character.head_y() - screen_origin_position.y - self.px(OVERHEAD_BAR_OFFSET) - height;
+        self.draw_bar(Rect::new(x, y, width, height), player.character.hp / player.stats.max_hp, HEALTH_COLOR);
+        player.statuses.draw_indicators(Vec2 { x: x + width / 2., y: y - self.px(STATUS_OFFSET) });
+    }
+
+    /// Kills and gold, in the upper right corner
+    fn draw_counters(&self, kills: i16, gold: u32) {
+        let mut y = self.px(XP_BAR_HEIGHT + MARGIN);
+        for text in [format!("Kills {kills}"), format!("Gold {gold}")] {
+            y += self.text_height(&text, FONT_SIZE);
+            self.draw_text(&text, screen_width() - self.px(MARGIN) - self.text_width(&text, FONT_SIZE), y,
+                FONT_SIZE, WHITE);
+            y += self.px(MARGIN) / 2.;
+        }
+    }
+
+    /// A row of weapon slots then a row of passive slots, empty slots included. Returns the bottom of the rows.
+    fn draw_slots(&self, player: &Player, top: f32) -> f32 {
+        let step = self.px(SLOT_SIZE + SLOT_GAP);
+        for i in 0..MAX_WEAPONS {
+            let rect = Rect::new(self.px(MARGIN) + i as f32 * step, top, self.px(SLOT_SIZE), self.px(SLOT_SIZE));
+            self.draw_weapon_slot(rect, player.weapons.get(i));
+        }
+        let top = top + step;
+        for i in 0..MAX_PASSIVES {
+            let rect = Rect::new(self.px(MARGIN) + i as f32 * step, top, self.px(SLOT_SIZE), self.px(SLOT_SIZE));
+            self.draw_passive_slot(rect, player.passives.get(i));
+        }
+        top + self.px(SLOT_SIZE)
+    }
+
+    fn draw_weapon_slot(&self, rect: Rect, weapon: Option<&WeaponSlot>) {
+        let Some(weapon) = weapon else {
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., EMPTY_SLOT_BORDER);
+            return;
+        };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, SLOT_BACKGROUND);
+        let icon = Rect::new(rect.x + self.px(SLOT_PADDING), rect.y + self.px(SLOT_PADDING),
+            rect.w - self.px(SLOT_PADDING) * 2., rect.h - self.px(SLOT_PADDING) * 2.);
+        match (weapon, weapon.sprite()) {
+            (_, Some(handle)) => {
+                // Fit the whole sprite in the slot, keeping its proportions
+                let sprite = self.assets.sprite(handle);
+                let size = sprite.size() * (icon.w / sprite.size().max_element());
+                let position = icon.center() - size / 2.;
+                draw_texture_ex(sprite.texture, position.x, position.y, WHITE, DrawTextureParams {
+                    source: Some(sprite.region_for_width(size.x)),
+                    dest_size: Some(size),
+                    ..Default::default()
+                });
+            }
+            (WeaponSlot::Aura(aura), None) => {
+                let center = icon.center();
+                draw_circle(center.x, center.y, icon.w / 2., aura.color());
+            }
+            _ => (),
+        }
+        self.draw_cooldown_sweep(rect, weapon.cooldown_left());
+        let border = if weapon.evolution().is_some() { EVOLVED_SLOT_BORDER } else { SLOT_BORDER };
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., border);
+        self.draw_pips(rect, weapon.level(), weapon.max_level());
+    }
+
+    fn draw_passive_slot(&self, rect: Rect, passive: Option<&(PassiveItem, u8)>) {
+        let Some((item, level)) = passive else {
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., EMPTY_SLOT_BORDER);
+            return;
+        };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, SLOT_BACKGROUND);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., SLOT_BORDER);
+        self.draw_text_centered(item.abbreviation(), rect.center(), FONT_SIZE, WHITE);
+        self.draw_pips(rect, *level, MAX_PASSIVE_LEVEL);
+    }
+
+    /// One pip per level under the slot, the reached levels being filled
+    fn draw_pips(&self, slot: Rect, level: u8, max_level: u8) {
+        let (size, gap) = (self.px(PIP_SIZE), self.px(PIP_GAP));
+        let width = max_level as f32 * (size + gap) - gap;
+        let x = slot.center().x - width / 2.;
+        for pip in 0..max_level {
+            let color = if pip < level { PIP_COLOR } else { EMPTY_PIP_COLOR };
+            draw_rectangle(x + pip as f32 * (size + gap), slot.bottom() + gap, size, size, color);
+        }
+    }
+
+    /// Darken the part of the slot matching the cooldown left, like a clock hand sweeping from the top
+    fn draw_cooldown_sweep(&self, slot: Rect, cooldown_left: f32) {
+        if cooldown_left <= 0. {
+            return;
+        }
+        let center = slot.center();
+        // Point of the border of the slot in the direction of the angle
+        let border_point = |angle: f32| {
+            let direction = Vec2::from_angle(angle);
+            center + direction * (slot.w / 2. / direction.x.abs().max(direction.y.abs()))
+        };
+        let start = -FRAC_PI_2 + TAU * (1. - cooldown_left);
+        let segments = (COOLDOWN_SEGMENTS * cooldown_left).ceil();
+        let step = TAU * cooldown_left / segments;
+        for i in 0..segments as usize {
+            let angle = start + i as f32 * step;
+            draw_triangle(center, border_point(angle), border_point(angle + step), COOLDOWN_COLOR);
+        }
+    }
+
+    /// Dash charges and a bar filling up while the next charge recovers, in the lower left corner
+    fn draw_dash(&self, dash: &Dash) {
+        let bar = Rect::new(self.px(MARGIN), screen_height() - self.px(MARGIN + DASH_BAR_HEIGHT),
+            self.px(DASH_BAR_WIDTH), self.px(DASH_BAR_HEIGHT));
+        self.draw_text(&format!("Dash : {}/{}", dash.charges, dash.max_charges), bar.x, bar.y - self.px(4.),
+            FONT_SIZE, WHITE);
+        self.draw_bar(bar, dash.cooldown_progress(), SKYBLUE);
+    }
+
+    /// Health bars of the bosses, stacked up from the bottom of the screen with their name above them
+    fn draw_boss_bars(&self, bosses: &[BossBar]) {
+        let width = self.px(BOSS_BAR_WIDTH).min(screen_width() - self.px(MARGIN) * 2.);
+        let mut bottom = screen_height() - self.px(MARGIN);
+        for boss in bosses {
+            let bar = Rect::new((screen_width() - width) / 2., bottom - self.px(BOSS_BAR_HEIGHT), width,
+                self.px(BOSS_BAR_HEIGHT));
+            self.draw_bar(bar, boss.hp_ratio, BOSS_COLOR);
+            self.draw_text(boss.name, bar.x, bar.y - self.px(4.), FONT_SIZE, WHITE);
+            bottom = bar.y - self.px(4. + MARGIN) - self.text_height(boss.name, FONT_SIZE);
+        }
+    }
+
+    fn draw_bar(&self, rect: Rect, ratio: f32, color: Color) {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, BAR_BACKGROUND);
+        draw_rectangle(rect.x, rect.y, rect.w * ratio.clamp(0., 1.), rect.h, color);
+    }
+
+    /// Draw a text whose baseline is at `y`, the size being scaled
+    fn draw_text(&self, text: &str, x: f32, y: f32, size: f32, color: Color) {
+        draw_text_ex(text, x, y, TextParams {
+            font: self.font,
+            font_size: self.font_size(size),
+            color,
+            ..Default::default()
+        });
+    }
+
+    fn draw_text_centered(&self, text: &str, center: Vec2, size: f32, color: Color) {
+        let dimensions = measure_text(text, self.font, self.font_size(size), 1.);
+        self.draw_text(text, center.x - dimensions.width / 2., center.y + dimensions.offset_y / 2., size, color);
+    }
+
+    /// Baseline of a text vertically centered in the rectangle
+    fn baseline(&self, rect: Rect) -> f32 {
+        rect.center().y + self.text_height("L", FONT_SIZE) / 2.
+    }
+
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        measure_text(text, self.font, self.font_size(size), 1.).width
+    }
+
+    /// Height above the baseline
+    fn text_height(&self, text: &str, size: f32) -> f32 {
+        measure_text(text, self.font, self.font_size(size), 1.).offset_y
+    }
+
+    fn font_size(&self, size: f32) -> u16 {
+        (size * self.scale).round() as u16
+    }
+
+    fn px(&self, length: f32) -> f32 {
+        length * self.scale
+    }
+}
@@ -0,0 +1,38 @@
+use macroquad::prelude::*;
+
+const BAR_WIDTH: f32 = 400.;
+const BAR_HEIGHT: f32 = 20.;
+const ERROR_MARGIN: f32 = 20.; // Space between the error and the edges of the screen
+const ERROR_FONT_SIZE: f32 = 20.;
+
+/// Progress bar drawn while the assets are loaded, `progress` going from 0 to 1
+pub(crate) fn draw_loading_screen(progress: f32) {
+    clear_background(BLACK);
+    let x = screen_width() / 2. - BAR_WIDTH / 2.;
+    let y = screen_height() / 2. - BAR_HEIGHT / 2.;
+    draw_text(&format!("Loading... {:.0}%", progress * 100.), x, y - 10., 20., WHITE);
+    draw_rectangle(x, y, BAR_WIDTH, BAR_HEIGHT, DARKGRAY);
+    draw_rectangle(x, y, BAR_WIDTH * progress, BAR_HEIGHT, WHITE);
+}
+
+/// Error preventing the definitions from loading, wrapped to the width of the screen,
+/// drawn until the files are fixed
+pub(crate) fn draw_loading_error(error: &str) {
+    clear_background(BLACK);
+    let max_width = screen_width() - 2. * ERROR_MARGIN;
+    let mut lines = vec!["Could not load the game data. Fix the file then press any key to retry.".to_string()];
+    let mut line = String::new();
+    for word in error.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{line} {word}") };
+        if !line.is_empty() && measure_text(&candidate, None, ERROR_FONT_SIZE as u16, 1.).width > max_width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    lines.push(line);
+    for (i, line) in lines.iter().enumerate() {
+        let color = if i == 0 { WHITE } else { RED };
+        draw_text(line, ERROR_MARGIN, ERROR_MARGIN + (i + 1) as f32 * ERROR_FONT_SIZE, ERROR_FONT_SIZE, color);
+    }
+}
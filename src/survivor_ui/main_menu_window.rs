@@ -2,48 +2,52 @@ use macroquad::prelude::*;
 
 use macroquad::ui::{hash, root_ui, widgets};
 
+use crate::asset_manager::{AssetManager, SkinHandle};
+use crate::input::input_map::InputMap;
 use crate::survivor_ui::window::{Action, Window};
 
 const WINDOW_WIDTH: f32 = 300.;
 const WINDOW_HEIGHT: f32 = 400.;
 const WINDOW_POSITION: Vec2 = Vec2::new(800. / 2. - WINDOW_WIDTH / 2., 600. / 2. - WINDOW_HEIGHT / 2.);
 
+const BUTTONS: [(&str, Vec2, Action); 3] = [
+    ("Play", Vec2::new(65.0, 15.0), Action::Play),
+    ("Options", Vec2::new(40.0, 75.0), Action::Options),
+    ("Quit", Vec2::new(65.0, 195.0), Action::Quit),
+];
+
 pub(crate) struct MainMenuWindow {
     pub(crate) window: Window,
 }
 
 impl MainMenuWindow {
-    pub(crate) async fn new() -> Self {
+    pub(crate) fn new(skin: SkinHandle) -> Self {
         Self {
-            window: Window::new(WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_POSITION).await,
+            window: Window::new(WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_POSITION, skin),
         }
     }
 
-    pub(crate) fn draw(&self) -> Option<Action> {
-        root_ui().push_skin(&self.window.skin);
+    pub(crate) fn draw(&mut self, input_map: &InputMap, assets: &AssetManager) -> Option<Action> {
+        let confirmed = self.window.navigate(input_map, BUTTONS.len());
+        let focused = self.window.focused;
+
+        root_ui().push_skin(assets.skin(self.window.skin));
         
         root_ui().same_line(0.);
         
-        let mut action: Option<Action> = None;
+        let mut action: Option<Action> = confirmed.map(|i| BUTTONS[i].2);
 
         root_ui().window(
             hash!(), self.window.position, vec2(self.window.width, 
                 self.window.height), 
                 |ui| {
-            if widgets::Button::new("Play")
-            .position(vec2(65.0, 15.0))
-            .ui(ui) {
-                action = Some(Action::Play);
-            }
-            if widgets::Button::new("Options")
-            .position(vec2(40.0, 75.0))
-            .ui(ui) {
-                action = Some(Action::Options);
-            }
-            if widgets::Button::new("Quit")
-            .position(vec2(65.0, 195.0))
-            .ui(ui) {
-                action = Some(Action::Quit);
+            for (i, (label, position, button_action)) in BUTTONS.iter().enumerate() {
+                if widgets::Button::new(*label)
+                .position(*position)
+                .selected(i == focused)
+                .ui(ui) {
+                    action = Some(*button_action);
+                }
             }
         });
         action
@@ -0,0 +1,60 @@
+use macroquad::prelude::*;
+
+const MARGIN: f32 = 10.; // Space between the minimap and the edges of the screen, before scaling
+const BACKGROUND: Color = Color::new(0., 0., 0., 0.5);
+const BORDER_COLOR: Color = Color::new(0.8, 0.8, 0.8, 0.8);
+const VIEW_COLOR: Color = Color::new(1., 1., 1., 0.3);
+const PLAYER_COLOR: Color = Color::new(0.3, 0.8, 1., 1.);
+const ENNEMY_COLOR: Color = Color::new(0.9, 0.2, 0.2, 0.8);
+const BOSS_COLOR: Color = Color::new(0.8, 0.2, 0.9, 1.);
+const POINT_OF_INTEREST_COLOR: Color = Color::new(1., 0.8, 0.1, 1.);
+const PLAYER_DOT_SIZE: f32 = 3.; // Radius of the player dot, before scaling
+const ENNEMY_DOT_SIZE: f32 = 1.5;
+const BOSS_DOT_SIZE: f32 = 4.;
+const POINT_OF_INTEREST_SIZE: f32 = 3.; // Half the side of the diamond marking a point of interest
+
+/// What the minimap shows, in world coordinates
+pub(crate) struct MinimapInfo {
+    /// Bounds of the world
+    pub(crate) map: Rect,
+    /// Part of the world on the screen
+    pub(crate) view: Rect,
+    pub(crate) player: Vec2,
+    pub(crate) ennemies: Vec<Vec2>,
+    pub(crate) bosses: Vec<Vec2>,
+    /// Places worth going to, like treasure chests
+    pub(crate) points_of_interest: Vec<Vec2>,
+}
+
+/// Map of the whole world in the lower right corner of the screen, `size` being its side before scaling.
+/// Ennemies are dots, so their density shows where the crowds are. Anything outside of the world,
+/// like ennemies spawning past its edges, is drawn on its border.
+pub(crate) fn draw_minimap(info: &MinimapInfo, size: f32, scale: f32) {
+    let side = size * scale;
+    let area = Rect::new(screen_width() - side - MARGIN * scale, screen_height() - side - MARGIN * scale,
+        side, side);
+    let to_minimap = |world_position: Vec2| {
+        let ratio = (world_position - info.map.point()) / info.map.size();
+        area.point() + ratio.clamp(Vec2::ZERO, Vec2::ONE) * area.size()
+    };
+
+    draw_rectangle(area.x, area.y, area.w, area.h, BACKGROUND);
+    let view_start = to_minimap(info.view.point());
+    let view_size = to_minimap(info.view.point() + info.view.size()) - view_start;
+    draw_rectangle_lines(view_start.x, view_start.y, view_size.x, view_size.y, 1., VIEW_COLOR);
+    for ennemy in &info.ennemies {
+        let position = to_minimap(*ennemy);
+        draw_circle(position.x, position.y, ENNEMY_DOT_SIZE * scale, ENNEMY_COLOR);
+    }
+    for point in &info.points_of_interest {
+        let position = to_minimap(*point);
+        draw_poly(position.x, position.y, 4, POINT_OF_INTEREST_SIZE * scale, 0., POINT_OF_INTEREST_COLOR);
+    }
+    for boss in &info.bosses {
+        let position = to_minimap(*boss);
+        draw_circle(position.x, position.y, BOSS_DOT_SIZE * scale, BOSS_COLOR);
+    }
+    let player = to_minimap(info.player);
+    draw_circle(player.x, player.y, PLAYER_DOT_SIZE * scale, PLAYER_COLOR);
+    draw_rectangle_lines(area.x, area.y, area.w, area.h, 1., BORDER_COLOR);
+}
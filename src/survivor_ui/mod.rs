@@ -1,3 +1,10 @@
 pub(crate) mod window;
 pub(crate) mod main_menu_window;
-pub(crate) mod pause_window;
\ No newline at end of file
+pub(crate) mod pause_window;
+pub(crate) mod options_window;
+pub(crate) mod character_select_window;
+pub(crate) mod skin;
+pub(crate) mod loading_screen;
+pub(crate) mod toast;
+pub(crate) mod hud;
+pub(crate) mod minimap;
@@ -0,0 +1,251 @@
+use macroquad::prelude::*;
+
+use macroquad::ui::{hash, root_ui, widgets};
+
+use crate::asset_manager::{AssetManager, SkinHandle};
+use crate::input::{action::InputAction, bindings::{Binding, InputBindings}, input_map::InputMap};
+use crate::settings::Settings;
+use crate::survivor_ui::window::{Action, Window};
+
+const WINDOW_WIDTH: f32 = 600.;
+const WINDOW_HEIGHT: f32 = 580.;
+const WINDOW_POSITION: Vec2 = Vec2::new(800. / 2. - WINDOW_WIDTH / 2., 600. / 2. - WINDOW_HEIGHT / 2.);
+const ROW_HEIGHT: f32 = 45.; // Vertical space between two bindings or settings
+const BINDING_COLUMN_X: f32 = 230.; // Horizontal position of the binding and setting buttons
+const PARTICLE_BUDGETS: [usize; 5] = [0, 250, 500, 1000, 2000]; // Particle budgets the options cycle through
+const UI_SCALES: [f32; 5] = [0.75, 1., 1.25, 1.5, 2.];
+const MINIMAP_SIZES: [f32; 4] = [120., 160., 200., 240.]; // Sides of the minimap in pixels, before the UI scale
+
+/// The options are split in two pages so they fit on the screen
+#[derive(Clone, Copy, PartialEq)]
+enum OptionsPage {
+    Controls,
+    Game,
+}
+
+/// Settings changed from the game page, each button cycling through the values of its setting
+#[derive(Clone, Copy)]
+enum GameSetting {
+    DashAfterimages,
+    DamageNumbers,
+    HitFlash,
+    HitStop,
+    ParticleBudget,
+    ReducedMotion,
+    UiScale,
+    MinimapSize,
+}
+
+impl GameSetting {
+    const ALL: [GameSetting; 8] = [
+        GameSetting::DashAfterimages,
+        GameSetting::DamageNumbers,
+        GameSetting::HitFlash,
+        GameSetting::HitStop,
+        GameSetting::ParticleBudget,
+        GameSetting::ReducedMotion,
+        GameSetting::UiScale,
+        GameSetting::MinimapSize,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            GameSetting::DashAfterimages => "Dash afterimages",
+            GameSetting::DamageNumbers => "Damage numbers",
+            GameSetting::HitFlash => "Hit flash",
+            GameSetting::HitStop => "Hit-stop",
+            GameSetting::ParticleBudget => "Particles",
+            GameSetting::ReducedMotion => "Reduced motion",
+            GameSetting::UiScale => "HUD size",
+            GameSetting::MinimapSize => "Minimap size",
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> String {
+        match self {
+            GameSetting::DashAfterimages => on_off(settings.dash_afterimages),
+            GameSetting::DamageNumbers => on_off(settings.damage_numbers),
+            GameSetting::HitFlash => on_off(settings.hit_flash),
+            GameSetting::HitStop => on_off(settings.hit_stop),
+            GameSetting::ParticleBudget if settings.particle_budget == 0 => "Off".to_string(),
+            GameSetting::ParticleBudget => format!("Up to {}", settings.particle_budget),
+            GameSetting::ReducedMotion => on_off(settings.reduced_motion),
+            GameSetting::UiScale => format!("x{}", settings.ui_scale()),
+            GameSetting::MinimapSize => format!("{} px", settings.minimap_size),
+        }
+    }
+
+    /// Switch to the next value of the setting
+    fn change(&self, settings: &mut Settings) {
+        match self {
+            GameSetting::DashAfterimages => settings.dash_afterimages = !settings.dash_afterimages,
+            GameSetting::DamageNumbers => settings.damage_numbers = !settings.damage_numbers,
+            GameSetting::HitFlash => settings.hit_flash = !settings.hit_flash,
+            GameSetting::HitStop => settings.hit_stop = !settings.hit_stop,
+            GameSetting::ParticleBudget => settings.particle_budget = next_value(&PARTICLE_BUDGETS,
+                settings.particle_budget),
+            GameSetting::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+            GameSetting::UiScale => settings.ui_scale = next_value(&UI_SCALES, settings.ui_scale()),
+            GameSetting::MinimapSize => settings.minimap_size = next_value(&MINIMAP_SIZES, settings.minimap_size),
+        }
+    }
+}
+
+pub(crate) struct OptionsWindow {
+    pub(crate) window: Window,
+    page: OptionsPage,
+    /// Action waiting for a key to be pressed to get its new primary binding
+    listening: Option<InputAction>,
+}
+
+impl OptionsWindow {
+    pub(crate) fn new(skin: SkinHandle) -> Self {
+        Self {
+            window: Window::new(WINDOW_WIDTH, WINDOW_HEIGHT, WINDOW_POSITION, skin),
+            page: OptionsPage::Controls,
+            listening: None,
+        }
+    }
+
+    /// The settings are saved by the caller when the window is left
+    pub(crate) fn draw(&mut self, input_map: &mut InputMap, settings: &mut Settings, assets: &AssetManager)
+        -> Option<Action> {
+        // The page button and the rows of the page, then the "Defaults" button of the controls and "Back"
+        let (nb_rows, back) = match self.page {
+            OptionsPage::Controls => (InputAction::ALL.len() + 1, InputAction::ALL.len() + 2),
+            OptionsPage::Game => (GameSetting::ALL.len() + 1, GameSetting::ALL.len() + 1),
+        };
+        let nb_buttons = back + 1;
+        let mut confirmed = None;
+
+        if let Some(listened_action) = self.listening {
+            if let Some(binding) = input_map.last_pressed() {
+                // Escape cancels the rebinding instead of being bound
+                if binding != Binding::Key(KeyCode::Escape) {
+                    input_map.bindings.set_primary(listened_action, binding);
+                }
+                self.listening = None;
+            }
+        } else {
+            confirmed = self.window.navigate(input_map, nb_buttons);
+        }
+        let focused = self.window.focused;
+        let bindings = &mut input_map.bindings;
+        let page = self.page;
+
+        root_ui().push_skin(assets.skin(self.window.skin));
+
+        root_ui().same_line(0.);
+
+        let mut action: Option<Action> = None;
+        let listening = self.listening;
+        let mut switch_page = false;
+        let mut clicked_action: Option<InputAction> = None;
+        let mut clicked_setting: Option<GameSetting> = None;
+        let mut reset_bindings = false;
+        match (confirmed, page) {
+            (Some(0), _) => switch_page = true,
+            (Some(i), OptionsPage::Controls) if i <= InputAction::ALL.len() => clicked_action = Some(InputAction::ALL[i - 1]),
+            (Some(i), OptionsPage::Controls) if i == InputAction::ALL.len() + 1 => reset_bindings = true,
+            (Some(i), OptionsPage::Game) if i <= GameSetting::ALL.len() => clicked_setting = Some(GameSetting::ALL[i - 1]),
+            (Some(_), _) => action = Some(Action::Back),
+            (None, _) => (),
+        }
+
+        root_ui().window(
+            hash!(), self.window.position, vec2(self.window.width,
+                self.window.height),
+                |ui| {
+            let page_text = match page {
+                OptionsPage::Controls => "Controls  >  Game",
+                OptionsPage::Game => "Game  >  Controls",
+            };
+            if widgets::Button::new(page_text)
+            .position(vec2(20.0, 15.0))
+            .selected(focused == 0)
+            .ui(ui) {
+                switch_page = true;
+            }
+
+            match page {
+                OptionsPage::Controls => {
+                    for (i, input_action) in InputAction::ALL.iter().enumerate() {
+                        let y = 15.0 + (i + 1) as f32 * ROW_HEIGHT;
+                        ui.label(vec2(20.0, y + 10.0), input_action.label());
+
+                        let binding_text = if listening == Some(*input_action) {
+                            "Press a key...".to_string()
+                        } else {
+                            bindings.get(*input_action).iter()
+                                .map(Binding::name)
+                                .collect::<Vec<String>>()
+                                .join(" / ")
+                        };
+                        if widgets::Button::new(binding_text)
+                        .position(vec2(BINDING_COLUMN_X, y))
+                        .selected(i + 1 == focused)
+                        .ui(ui) {
+                            clicked_action = Some(*input_action);
+                        }
+                    }
+                }
+                OptionsPage::Game => {
+                    for (i, setting) in GameSetting::ALL.iter().enumerate() {
+                        let y = 15.0 + (i + 1) as f32 * ROW_HEIGHT;
+                        ui.label(vec2(20.0, y + 10.0), setting.label());
+                        if widgets::Button::new(setting.value(settings))
+                        .position(vec2(BINDING_COLUMN_X, y))
+                        .selected(i + 1 == focused)
+                        .ui(ui) {
+                            clicked_setting = Some(*setting);
+                        }
+                    }
+                }
+            }
+
+            let y = 25.0 + (InputAction::ALL.len() + 1) as f32 * ROW_HEIGHT;
+            if page == OptionsPage::Controls && widgets::Button::new("Defaults")
+            .position(vec2(20.0, y))
+            .selected(focused == nb_rows)
+            .ui(ui) {
+                reset_bindings = true;
+            }
+            if widgets::Button::new("Back")
+            .position(vec2(BINDING_COLUMN_X, y))
+            .selected(focused == back)
+            .ui(ui) {
+                action = Some(Action::Back);
+            }
+        });
+
+        if reset_bindings {
+            *bindings = InputBindings::default();
+        }
+        if let Some(setting) = clicked_setting {
+            setting.change(settings);
+        }
+        if clicked_action.is_some() {
+            self.listening = clicked_action;
+        }
+        if switch_page {
+            self.page = match page {
+                OptionsPage::Controls => OptionsPage::Game,
+                OptionsPage::Game => OptionsPage::Controls,
+            };
+            self.window.focused = 0;
+        }
+        if action.is_some() {
+            self.listening = None;
+        }
+        action
+    }
+}
+
+fn on_off(value: bool) -> String {
+    if value { "On" } else { "Off" }.to_string()
+}
+
+/// The value following `current` in the ascending `values`, going back to the first one after the last one
+fn next_value<T: Copy + PartialOrd>(values: &[T], current: T) -> T {
+    values.iter().copied().find(|value| *value > current).unwrap_or(values[0])
+}
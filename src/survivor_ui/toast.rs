@@ -0,0 +1,56 @@
+use macroquad::prelude::*;
+
+const TOAST_DURATION: f32 = 4.; // Seconds during which a toast is shown
+const TOAST_FONT_SIZE: f32 = 20.;
+const TOAST_HEIGHT: f32 = 26.; // Vertical space taken by each toast
+const TOAST_MARGIN: f32 = 10.; // Space between the toasts and the edges of the screen
+const TOAST_BACKGROUND: Color = Color::new(0., 0., 0., 0.7);
+const ERROR_COLOR: Color = Color::new(1., 0.4, 0.4, 1.);
+
+struct Toast {
+    message: String,
+    color: Color,
+    timer: f32,
+}
+
+/// Short messages stacked in the upper right corner of the screen, fading out after a few seconds
+pub(crate) struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    pub(crate) fn new() -> Self {
+        Self { toasts: Vec::new() }
+    }
+
+    pub(crate) fn info(&mut self, message: String) {
+        self.push(message, WHITE);
+    }
+
+    /// The error is also printed, as it may not fit on the screen
+    pub(crate) fn error(&mut self, message: String) {
+        println!("{message}");
+        self.push(message, ERROR_COLOR);
+    }
+
+    fn push(&mut self, message: String, color: Color) {
+        self.toasts.push(Toast { message, color, timer: TOAST_DURATION });
+    }
+
+    pub(crate) fn draw(&mut self) {
+        let dt = get_frame_time();
+        self.toasts.retain_mut(|toast| {
+            toast.timer -= dt;
+            toast.timer > 0.
+        });
+        for (i, toast) in self.toasts.iter().enumerate() {
+            // Fade out during the last second
+            let alpha = toast.timer.min(1.);
+            let width = measure_text(&toast.message, None, TOAST_FONT_SIZE as u16, 1.).width;
+            let x = screen_width() - width - TOAST_MARGIN;
+            let y = TOAST_MARGIN + i as f32 * TOAST_HEIGHT;
+            draw_rectangle(x - 4., y, width + 8., TOAST_HEIGHT - 2., TOAST_BACKGROUND.with_alpha(TOAST_BACKGROUND.a * alpha));
+            draw_text(&toast.message, x, y + TOAST_FONT_SIZE - 2., TOAST_FONT_SIZE, toast.color.with_alpha(alpha));
+        }
+    }
+}
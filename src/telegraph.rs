@@ -0,0 +1,111 @@
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+
+const CONE_SEGMENTS: u8 = 16; // Triangles drawing a full circle, cones using their share of them
+const OUTLINE_THICKNESS: f32 = 2.;
+
+/// Area of the ground about to be hit, from an origin in world coordinates
+#[derive(Clone, Copy)]
+pub(crate) enum TelegraphShape {
+    /// Centered on the origin
+    Circle { radius: f32 },
+    /// From the origin towards `direction`, a unit vector, spreading `half_angle` radians on each side
+    Cone { direction: Vec2, half_angle: f32, radius: f32 },
+    /// From the origin towards `direction`, a unit vector
+    Line { direction: Vec2, length: f32, width: f32 },
+}
+
+impl TelegraphShape {
+    pub(crate) fn contains(&self, origin: Vec2, point: Vec2) -> bool {
+        let offset = point - origin;
+        match *self {
+            TelegraphShape::Circle { radius } => offset.length() <= radius,
+            TelegraphShape::Cone { direction, half_angle, radius } => {
+                offset.length() <= radius && (offset == Vec2::ZERO || direction.angle_between(offset).abs() <= half_angle)
+            }
+            TelegraphShape::Line { direction, length, width } => {
+                let along = offset.dot(direction);
+                (0. ..=length).contains(&along) && offset.perp_dot(direction).abs() <= width / 2.
+            }
+        }
+    }
+
+    /// Draw the outline of the area, filled up to `progress`, from 0 when the warning starts to 1 when it lands
+    pub(crate) fn draw(&self, screen_position: Vec2, progress: f32, color: Color) {
+        let (x, y) = (screen_position.x, screen_position.y);
+        let outline_color = color.with_alpha((color.a * 2.).min(1.));
+        match *self {
+            TelegraphShape::Circle { radius } => {
+                draw_circle(x, y, radius * progress, color);
+                draw_circle_lines(x, y, radius, OUTLINE_THICKNESS, outline_color);
+            }
+            TelegraphShape::Cone { direction, half_angle, radius } => {
+                draw_cone(screen_position, direction, half_angle, radius * progress, color);
+                let left = screen_position + Vec2::from_angle(-half_angle).rotate(direction) * radius;
+                let right = screen_position + Vec2::from_angle(half_angle).rotate(direction) * radius;
+                draw_line(x, y, left.x, left.y, OUTLINE_THICKNESS, outline_color);
+                draw_line(x, y, right.x, right.y, OUTLINE_THICKNESS, outline_color);
+            }
+            TelegraphShape::Line { direction, length, width } => {
+                let end = screen_position + direction * length;
+                let filled = screen_position + direction * length * progress;
+                draw_line(x, y, end.x, end.y, width, color.with_alpha(color.a / 2.));
+                draw_line(x, y, filled.x, filled.y, width, color);
+            }
+        }
+    }
+}
+
+/// Warning of a shape on the ground during `duration` seconds, before the damage lands
+pub(crate) struct Telegraph {
+    pub(crate) world_position: Vec2,
+    pub(crate) shape: TelegraphShape,
+    duration: f32,
+    timer: f32,
+}
+
+impl Telegraph {
+    pub(crate) fn new(world_position: Vec2, shape: TelegraphShape, duration: f32) -> Self {
+        Self { world_position, shape, duration, timer: 0. }
+    }
+
+    /// Returns true once the warning is over and the damage lands
+    pub(crate) fn update(&mut self) -> bool {
+        self.timer += get_frame_time();
+        self.is_done()
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.timer >= self.duration
+    }
+
+    pub(crate) fn progress(&self) -> f32 {
+        if self.duration > 0. { (self.timer / self.duration).min(1.) } else { 1. }
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2, color: Color) {
+        let screen_position = self.world_position - screen_origin_position;
+        // Shapes can reach the screen from an origin out of it
+        let reach = match self.shape {
+            TelegraphShape::Circle { radius } | TelegraphShape::Cone { radius, .. } => radius,
+            TelegraphShape::Line { length, .. } => length,
+        };
+        let bounds = Rect::new(screen_position.x - reach, screen_position.y - reach, reach * 2., reach * 2.);
+        if !bounds.overlaps(&Rect::new(0., 0., screen_width(), screen_height())) {
+            return;
+        }
+        self.shape.draw(screen_position, self.progress(), color);
+    }
+}
+
+/// Filled part of a circle, as a fan of triangles
+fn draw_cone(center: Vec2, direction: Vec2, half_angle: f32, radius: f32, color: Color) {
+    let segments = ((CONE_SEGMENTS as f32 * half_angle * 2. / TAU).ceil() as u8).max(1);
+    let step = half_angle * 2. / segments as f32;
+    for i in 0..segments {
+        let from = Vec2::from_angle(-half_angle + i as f32 * step).rotate(direction) * radius;
+        let to = Vec2::from_angle(-half_angle + (i + 1) as f32 * step).rotate(direction) * radius;
+        draw_triangle(center, center + from, center + to, color);
+    }
+}
@@ -1,22 +1,68 @@
 use macroquad::prelude::*;
 
-use crate::{collision::{Collidable, Hitbox}};
-
-const AURA_COLOR: Color = DARKPURPLE;
-const AURA_OPACITY: f32 = 0.5;
+use crate::{collision::{Collidable, CollisionLayer, Hitbox}, entity::stats::Stats, weapons::weapon_definition::{AuraDefinition, EvolutionDefinition, OnHit}};
 
 pub(crate) struct Aura {
     pub(crate) circle: Circle,
+    definition: AuraDefinition,
+    pub(crate) level: u8,
+    pub(crate) evolution: Option<EvolutionDefinition>,
 }
 
 impl Aura{
+    pub(crate) fn new(position: Vec2, definition: &AuraDefinition) -> Self {
+        Self {
+            circle: Circle::new(position.x, position.y, definition.radius),
+            definition: definition.clone(),
+            level: 1,
+            evolution: None,
+        }
+    }
+
+    /// Follow the player and grow with the area stat
+    pub fn update(&mut self, position: Vec2, stats: &Stats) {
+        let levels = (self.level - 1) as f32;
+        self.circle.x = position.x;
+        self.circle.y = position.y;
+        let evolution_area = self.evolution.as_ref().map_or(1., |evolution| evolution.area);
+        self.circle.r = self.definition.radius * stats.area * (1. + self.definition.per_level.area * levels)
+            * evolution_area;
+    }
+
+    pub fn damage(&self, stats: &Stats) -> f32 {
+        let levels = (self.level - 1) as f32;
+        let evolution_damage = self.evolution.as_ref().map_or(1., |evolution| evolution.damage);
+        (self.definition.damage + self.definition.per_level.damage * levels) * stats.might * evolution_damage
+    }
+
+    /// Use a reloaded definition, keeping the level within the new maximum
+    pub(crate) fn apply_definition(&mut self, definition: &AuraDefinition) {
+        self.definition = definition.clone();
+        self.level = self.level.min(definition.max_level);
+    }
+
+    pub(crate) fn max_level(&self) -> u8 {
+        self.definition.max_level
+    }
+
+    pub(crate) fn on_hit(&self) -> &OnHit {
+        &self.definition.on_hit
+    }
+
+    /// Color of the definition, tinted by the evolution
+    pub(crate) fn color(&self) -> Color {
+        let [r, g, b, a] = self.definition.color;
+        let tint = self.evolution.as_ref().map_or(WHITE, EvolutionDefinition::tint);
+        Color::new(r * tint.r, g * tint.g, b * tint.b, a * tint.a)
+    }
+
     /// Draw the sword taking into account its rotation and position
     pub fn draw(&self, screen_center_position: Vec2) {
         let screen_position = Vec2 {
             x: self.circle.x - screen_center_position.x,
             y: self.circle.y - screen_center_position.y,
         };
-        draw_circle(screen_position.x, screen_position.y, self.circle.r, AURA_COLOR.with_alpha(AURA_OPACITY));
+        draw_circle(screen_position.x, screen_position.y, self.circle.r, self.color());
     }
 }
 
@@ -24,4 +70,8 @@ impl Collidable for Aura {
     fn hitbox(&self) -> Hitbox {
         Hitbox::Circle(self.circle)
     }
-}
\ No newline at end of file
+
+    fn layer(&self) -> CollisionLayer {
+        CollisionLayer::PlayerWeapon
+    }
+}
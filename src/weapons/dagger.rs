@@ -1,51 +1,132 @@
 use macroquad::prelude::*;
-use crate::{collision::{Collidable, Hitbox, HitboxParams, hitbox_intersects}, weapons::weapon::{OBBWeapon, WeaponHitboxParams}};
+use crate::{asset_manager::{AssetManager, SpriteHandle}, collision::{Collidable, collides}, entity::stats::Stats, weapons::{weapon::OBBWeapon, weapon_definition::{DaggerDefinition, EvolutionDefinition, OnHit}}};
 
-// The dagger hitbox is 60% of the png size from the tip of the dagger to the handle
-const HITBOX_WIDTH_RATIO: f32 = 0.51;
-const HITBOX_HEIGTH_RATIO: f32 = 0.55; // Blade width = 115 pixels, png height is 215 px
-const DAGGER_WIDTH: f32 = 951.;
-const DAGGER_HEIGHT: f32 = 256.;
+const SHADOW_OFFSET: f32 = 20.; // Height at which the daggers fly above their shadow
+const SHADOW_WIDTH: f32 = 8.;
+const SHADOW_COLOR: Color = Color::new(0., 0., 0., 0.3);
 
 pub struct DaggerAggregate {
     daggers: Vec<Dagger>,
-    texture: Texture2D,
+    sprite: SpriteHandle,
+    cooldown_timer: f32,
+    /// Cooldown started by the last throw
+    cooldown: f32,
+    definition: DaggerDefinition,
+    pub(crate) level: u8,
+    pub(crate) evolution: Option<EvolutionDefinition>,
 }
 
 struct Dagger {
     weapon: OBBWeapon,
     vel: Vec2,
+    timer: f32,
+    lifetime: f32,
 }
 
 impl DaggerAggregate {
-    pub fn new(texture: Texture2D) -> Self {
+    pub(crate) fn new(definition: &DaggerDefinition, sprite: SpriteHandle) -> Self {
         Self {
             daggers: Vec::new(),
-            texture,
+            sprite,
+            cooldown_timer: 0.,
+            cooldown: 0.,
+            definition: definition.clone(),
+            level: 1,
+            evolution: None,
         }
     }
 
-    pub fn new_dagger(&mut self, position: Vec2, vel: Vec2, angle: f32, size_ratio: f32) {
-        let weapon = OBBWeapon::new(position, angle, size_ratio, WeaponHitboxParams {
-            params: HitboxParams {
-                size: Vec2 { x: DAGGER_WIDTH, y: DAGGER_HEIGHT},
-                offset_frame: Vec2 { x: 0., y: 0. },
-            },
-            width_ratio: HITBOX_WIDTH_RATIO,
-            height_ratio: HITBOX_HEIGTH_RATIO,
-        });
-        self.daggers.push(Dagger { weapon, vel });
+    pub(crate) fn is_ready(&self) -> bool {
+        self.cooldown_timer <= 0.
     }
 
-    pub fn update(&mut self) {
+    /// Fraction of the cooldown left before the next throw
+    pub(crate) fn cooldown_left(&self) -> f32 {
+        if self.cooldown > 0. { (self.cooldown_timer / self.cooldown).clamp(0., 1.) } else { 0. }
+    }
+
+    pub(crate) fn sprite(&self) -> SpriteHandle {
+        self.sprite
+    }
+
+    /// Throw a fan of daggers in the given direction, one dagger plus one per extra amount
+    pub fn throw(&mut self, position: Vec2, direction: Vec2, stats: &Stats) {
+        if !self.is_ready() {
+            return;
+        }
+        let levels = (self.level - 1) as f32;
+        let per_level = self.definition.per_level;
+        self.cooldown = stats.cooldown(self.definition.cooldown) * (1. - per_level.cooldown * levels);
+        self.cooldown_timer = self.cooldown;
+
+        let (evolution_amount, evolution_area) = self.evolution.as_ref()
+            .map_or((0, 1.), |evolution| (evolution.amount, evolution.area));
+        let nb_daggers = 1 + stats.amount as usize + (per_level.amount * levels) as usize + evolution_amount as usize;
+        let base_angle = direction.y.atan2(direction.x);
+        let speed = self.definition.speed * stats.projectile_speed * (1. + per_level.speed * levels);
+        let size_ratio = self.definition.size_ratio * stats.area * (1. + per_level.area * levels) * evolution_area;
+        for i in 0..nb_daggers {
+            let angle = base_angle + (i as f32 - (nb_daggers - 1) as f32 / 2.) * self.definition.spread;
+            let vel = Vec2::from_angle(angle) * speed;
+            self.new_dagger(position, vel, angle, size_ratio, self.definition.lifetime * stats.duration);
+        }
+    }
+
+    fn new_dagger(&mut self, position: Vec2, vel: Vec2, angle: f32, size_ratio: f32, lifetime: f32) {
+        let weapon = OBBWeapon::new(position, angle, size_ratio, self.definition.hitbox.clone());
+        self.daggers.push(Dagger { weapon, vel, timer: 0., lifetime });
+    }
+
+    /// Position and velocity of each flying dagger
+    pub(crate) fn daggers(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.daggers.iter().map(|dagger| (dagger.weapon.world_position, dagger.vel))
+    }
+
+    /// Move the daggers, the homing ones turning towards the closest of the targets
+    pub fn update(&mut self, targets: &[Vec2]) {
+        let dt = get_frame_time();
+        self.cooldown_timer -= dt;
+        let homing = self.evolution.as_ref().map_or(0., |evolution| evolution.homing);
         for dagger in &mut self.daggers {
+            if homing > 0. && let Some(target) = closest(targets, dagger.weapon.world_position) {
+                let to_target = target - dagger.weapon.world_position;
+                let turn = dagger.vel.angle_between(to_target).clamp(-homing * dt, homing * dt);
+                dagger.vel = Vec2::from_angle(turn).rotate(dagger.vel);
+                dagger.weapon.angle = dagger.vel.y.atan2(dagger.vel.x);
+            }
             dagger.weapon.world_position += dagger.vel;
+            dagger.timer += dt;
         }
+        self.daggers.retain(|dagger| dagger.timer < dagger.lifetime);
+    }
+
+    pub fn damage(&self, stats: &Stats) -> f32 {
+        let levels = (self.level - 1) as f32;
+        let evolution_damage = self.evolution.as_ref().map_or(1., |evolution| evolution.damage);
+        (self.definition.damage + self.definition.per_level.damage * levels) * stats.might * evolution_damage
+    }
+
+    /// Use a reloaded definition, keeping the level within the new maximum
+    pub(crate) fn apply_definition(&mut self, definition: &DaggerDefinition, sprite: SpriteHandle) {
+        for dagger in &mut self.daggers {
+            dagger.weapon.hitbox_params = definition.hitbox.clone();
+        }
+        self.definition = definition.clone();
+        self.sprite = sprite;
+        self.level = self.level.min(definition.max_level);
+    }
+
+    pub(crate) fn max_level(&self) -> u8 {
+        self.definition.max_level
+    }
+
+    pub(crate) fn on_hit(&self) -> &OnHit {
+        &self.definition.on_hit
     }
     
-    pub fn collide_with(&mut self, hitbox: &Hitbox) -> bool {
+    pub fn collide_with(&mut self, target: &impl Collidable) -> bool {
         if let Some(index) = self.daggers.iter().position(
-            |dagger| hitbox_intersects(hitbox, &dagger.weapon.hitbox())
+            |dagger| collides(&dagger.weapon, target)
         ) {
             self.daggers.swap_remove(index);
             return true;
@@ -53,16 +134,32 @@ impl DaggerAggregate {
         false
     }
 
-    pub(crate) fn draw(&self, screen_center_position: Vec2) {
+    /// Shadows on the ground under the flying daggers
+    pub(crate) fn draw_shadows(&self, screen_center_position: Vec2) {
         for dagger in &self.daggers {
-            dagger.weapon.draw(&self.texture, screen_center_position, Vec2 { 
+            let screen_position = dagger.weapon.world_position - screen_center_position;
+            draw_ellipse(screen_position.x, screen_position.y + SHADOW_OFFSET, SHADOW_WIDTH, SHADOW_WIDTH / 3., 0.,
+                SHADOW_COLOR);
+        }
+    }
+
+    pub(crate) fn draw(&self, screen_center_position: Vec2, assets: &AssetManager) {
+        let sprite = assets.sprite(self.sprite);
+        let color = self.evolution.as_ref().map_or(WHITE, EvolutionDefinition::tint);
+        for dagger in &self.daggers {
+            dagger.weapon.draw(&sprite, screen_center_position, Vec2 { 
                 x: 0., 
-                y: -(self.texture.size().y * dagger.weapon.size_ratio / 2.0) 
-            });
+                y: -(sprite.size().y * dagger.weapon.size_ratio / 2.0) 
+            }, color);
         }
     }
 }
 
+fn closest(targets: &[Vec2], position: Vec2) -> Option<Vec2> {
+    targets.iter().copied()
+        .min_by(|a, b| a.distance_squared(position).total_cmp(&b.distance_squared(position)))
+}
+
 // impl WeaponTrait for Dagger {
 //     fn update(&self) {
 //         // Update the dagger's state if necessary (e.g., animation, cooldown)
@@ -1,4 +1,6 @@
 pub mod weapon;
 pub mod dagger;
 pub mod sword;
-pub mod aura;
\ No newline at end of file
+pub mod aura;
+pub mod weapon_slot;
+pub mod weapon_definition;
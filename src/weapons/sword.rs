@@ -1,52 +1,87 @@
-use macroquad::prelude::*;
-use crate::{collision::{Collidable, Hitbox, HitboxParams}, weapons::weapon::{OBBWeapon, WeaponHitboxParams}};
-
-// The sword hitbox is 60% of the png size from the tip of the sword to the handle
-const HITBOX_WIDTH_RATIO: f32 = 0.7;
-const HITBOX_HEIGTH_RATIO: f32 = 0.53; // Blade width = 115 pixels, png height is 215 px
-const SWORD_WIDTH: f32 = 897.;
-const SWORD_HEIGHT: f32 = 216.;
+use std::f32::consts::TAU;
 
+use macroquad::prelude::*;
+use crate::{asset_manager::{AssetManager, SpriteHandle}, collision::{Collidable, collides}, entity::stats::Stats, weapons::{weapon::OBBWeapon, weapon_definition::{EvolutionDefinition, OnHit, SwordDefinition}}};
 
 pub struct Sword {
     pub(crate) weapon: OBBWeapon,
-    texture: Texture2D,
+    sprite: SpriteHandle,
+    definition: SwordDefinition,
+    pub(crate) level: u8,
+    pub(crate) evolution: Option<EvolutionDefinition>,
 }
 
 impl Sword {
-    pub fn new(position: Vec2, angle: f32, size_ratio: f32, texture: Texture2D) -> Self {
-        let hitbox_params = WeaponHitboxParams {
-            params: HitboxParams {
-                size: Vec2 { x: SWORD_WIDTH, y: SWORD_HEIGHT},
-                offset_frame: Vec2 { x: -20., y: 0. },
-            },
-            width_ratio: HITBOX_WIDTH_RATIO,
-            height_ratio: HITBOX_HEIGTH_RATIO,
-        };
+    pub(crate) fn new(position: Vec2, definition: &SwordDefinition, sprite: SpriteHandle) -> Self {
         Self {
-            weapon: OBBWeapon::new(position, angle, size_ratio, hitbox_params),
-            texture,
+            weapon: OBBWeapon::new(position, 0., definition.size_ratio, definition.hitbox.clone()),
+            sprite,
+            definition: definition.clone(),
+            level: 1,
+            evolution: None,
         }
     }
-    
-    pub fn update(&mut self) {
-        // For example, we can make the sword rotate over time
+
+    pub fn update(&mut self, stats: &Stats) {
+        // The sword spins faster with cooldown reduction, as it hits more often
         let dt = get_frame_time();
-        self.weapon.angle += 2.0 * dt; // Rotate at 2 radians per second
+        let levels = (self.level - 1) as f32;
+        let rotation_speed = self.definition.rotation_speed * (1. + self.definition.per_level.speed * levels);
+        self.weapon.angle += rotation_speed / stats.cooldown(1.) * dt;
+        let evolution_area = self.evolution.as_ref().map_or(1., |evolution| evolution.area);
+        self.weapon.size_ratio = self.definition.size_ratio * stats.area * (1. + self.definition.per_level.area * levels)
+            * evolution_area;
     }
 
-    /// Draw the sword taking into account its rotation and position
-    pub fn draw(&self, screen_center_position: Vec2) {
-        self.weapon.draw(&self.texture, screen_center_position, Vec2 { 
-            x: 20.0, 
-            y: -(self.texture.size().y * self.weapon.size_ratio / 2.0) 
-        });
+    pub fn damage(&self, stats: &Stats) -> f32 {
+        let levels = (self.level - 1) as f32;
+        let evolution_damage = self.evolution.as_ref().map_or(1., |evolution| evolution.damage);
+        (self.definition.damage + self.definition.per_level.damage * levels) * stats.might * evolution_damage
     }
-}
 
-/// Implement Collidable trait for Sword to provide its hitbox
-impl Collidable for Sword {
-    fn hitbox(&self) -> Hitbox {
-        self.weapon.hitbox()
+    /// Use a reloaded definition, keeping the level within the new maximum
+    pub(crate) fn apply_definition(&mut self, definition: &SwordDefinition, sprite: SpriteHandle) {
+        self.weapon.hitbox_params = definition.hitbox.clone();
+        self.definition = definition.clone();
+        self.sprite = sprite;
+        self.level = self.level.min(definition.max_level);
     }
-}
\ No newline at end of file
+
+    pub(crate) fn max_level(&self) -> u8 {
+        self.definition.max_level
+    }
+
+    pub(crate) fn on_hit(&self) -> &OnHit {
+        &self.definition.on_hit
+    }
+
+    pub(crate) fn sprite(&self) -> SpriteHandle {
+        self.sprite
+    }
+
+    /// The blade, plus the extra blades of the evolution spread evenly around the player
+    fn blades(&self) -> impl Iterator<Item = OBBWeapon> + '_ {
+        let nb_blades = 1 + self.evolution.as_ref().map_or(0, |evolution| evolution.amount);
+        (0..nb_blades).map(move |i| {
+            let mut blade = self.weapon.clone();
+            blade.angle += i as f32 * TAU / nb_blades as f32;
+            blade
+        })
+    }
+
+    pub(crate) fn hits(&self, target: &impl Collidable) -> bool {
+        self.blades().any(|blade| collides(&blade, target))
+    }
+
+    /// Draw the sword taking into account its rotation and position
+    pub(crate) fn draw(&self, screen_center_position: Vec2, assets: &AssetManager) {
+        let sprite = assets.sprite(self.sprite);
+        let color = self.evolution.as_ref().map_or(WHITE, EvolutionDefinition::tint);
+        for blade in self.blades() {
+            blade.draw(&sprite, screen_center_position, Vec2 {
+                x: self.definition.pivot_offset,
+                y: -(sprite.size().y * blade.size_ratio / 2.0)
+            }, color);
+        }
+    }
+}
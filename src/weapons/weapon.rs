@@ -1,12 +1,14 @@
 use macroquad::prelude::*;
+use serde::Deserialize;
 
-use crate::{collision::{self, Collidable, Hitbox, HitboxParams, OBB}, draw_utils::is_on_screen};
+use crate::{atlas::Sprite, collision::{self, Collidable, CollisionLayer, Hitbox, HitboxParams, OBB}, draw_utils::is_on_screen};
 
+#[derive(Clone)]
 pub struct OBBWeapon {
     pub world_position: Vec2,
     pub angle: f32,
     pub size_ratio: f32,
-    hitbox_params: WeaponHitboxParams,
+    pub(crate) hitbox_params: WeaponHitboxParams,
 }
 
 // pub trait WeaponTrait {
@@ -23,8 +25,9 @@ impl OBBWeapon {
             hitbox_params,
         }
     }
-    /// Draw the weapon taking into account its rotation and position
-    pub fn draw(&self, texture: &Texture2D, screen_origin_position: Vec2, offset: Vec2) {
+    /// Draw the weapon taking into account its rotation and position, using the pre-scaled version
+    /// of the sprite closest to the drawn size
+    pub(crate) fn draw(&self, sprite: &Sprite, screen_origin_position: Vec2, offset: Vec2, color: Color) {
         let screen_position = Vec2 {
             x: self.world_position.x - screen_origin_position.x,
             y: self.world_position.y - screen_origin_position.y,
@@ -43,16 +46,18 @@ impl OBBWeapon {
         };
 
         let texture_position = screen_position + offset;
+        let size = self.adjusted_size();
         
         draw_texture_ex(
-            texture,
+            sprite.texture,
             texture_position.x,
             texture_position.y,
-            WHITE,
+            color,
             DrawTextureParams {
+                source: Some(sprite.region_for_width(size.x)),
                 rotation: self.angle,
                 pivot: Some(pivot),
-                dest_size: Some(self.adjusted_size()),
+                dest_size: Some(size),
                 ..Default::default()
             },
         );
@@ -100,8 +105,13 @@ impl Collidable for OBBWeapon {
         })
 
     }
+
+    fn layer(&self) -> CollisionLayer {
+        CollisionLayer::PlayerWeapon
+    }
 }
 
+#[derive(Clone, Deserialize)]
 pub struct WeaponHitboxParams {
     pub params: HitboxParams,
     pub width_ratio: f32,
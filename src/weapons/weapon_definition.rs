@@ -0,0 +1,216 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{damage::DamageType, data::{ensure, ensure_non_negative, ensure_positive, ensure_ratio, load_ron}, entity::status::{StatusApplication, validate_statuses}, items::passive::PassiveItem, weapons::{weapon::WeaponHitboxParams, weapon_slot::WeaponKind}};
+
+pub(crate) const WEAPONS_PATH: &str = "assets/weapons.ron";
+
+/// Definitions of every weapon, as described in `assets/weapons.ron`
+#[derive(Clone, Deserialize)]
+pub(crate) struct WeaponDefinitions {
+    pub(crate) sword: SwordDefinition,
+    pub(crate) daggers: DaggerDefinition,
+    pub(crate) aura: AuraDefinition,
+    #[serde(default)]
+    pub(crate) evolutions: Vec<EvolutionDefinition>,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct SwordDefinition {
+    pub(crate) texture: String,
+    pub(crate) hitbox: WeaponHitboxParams,
+    /// Horizontal distance in pixels between the pivot and the handle of the drawn sword
+    pub(crate) pivot_offset: f32,
+    /// Size of the drawn texture compared to the png, before the area multiplier
+    pub(crate) size_ratio: f32,
+    /// Radians per second, before cooldown reduction
+    pub(crate) rotation_speed: f32,
+    pub(crate) damage: f32,
+    pub(crate) max_level: u8,
+    #[serde(default)]
+    pub(crate) per_level: WeaponScaling,
+    #[serde(default)]
+    pub(crate) on_hit: OnHit,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct DaggerDefinition {
+    pub(crate) texture: String,
+    pub(crate) hitbox: WeaponHitboxParams,
+    pub(crate) size_ratio: f32,
+    /// Distance travelled each frame before the projectile speed multiplier
+    pub(crate) speed: f32,
+    pub(crate) damage: f32,
+    /// Seconds before a dagger disappears, before the duration multiplier
+    pub(crate) lifetime: f32,
+    /// Seconds between two throws, before the cooldown reduction
+    pub(crate) cooldown: f32,
+    /// Angle in radians between daggers thrown at once
+    pub(crate) spread: f32,
+    pub(crate) max_level: u8,
+    #[serde(default)]
+    pub(crate) per_level: WeaponScaling,
+    #[serde(default)]
+    pub(crate) on_hit: OnHit,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct AuraDefinition {
+    pub(crate) color: [f32; 4],
+    /// Radius before the area multiplier
+    pub(crate) radius: f32,
+    pub(crate) damage: f32,
+    pub(crate) max_level: u8,
+    #[serde(default)]
+    pub(crate) per_level: WeaponScaling,
+    #[serde(default)]
+    pub(crate) on_hit: OnHit,
+}
+
+/// What a weapon does to the ennemies it hits besides damaging them
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct OnHit {
+    /// Speed in pixels per second at which the ennemies hit are pushed away, slowing down quickly
+    pub(crate) knockback: f32,
+    pub(crate) effects: Vec<StatusApplication>,
+    /// Type of the damage of the weapon, resisted on its own by the ennemies
+    pub(crate) damage_type: DamageType,
+}
+
+/// Stronger version of a weapon, which it turns into when a chest is opened while it is at its maximum
+/// level and the player owns the passive item
+#[derive(Clone, Deserialize)]
+pub(crate) struct EvolutionDefinition {
+    pub(crate) name: String,
+    pub(crate) weapon: WeaponKind,
+    pub(crate) passive: PassiveItem,
+    /// Color the evolved weapon is drawn with
+    #[serde(default = "default_tint")]
+    pub(crate) tint: [f32; 4],
+    /// Multiplier of the damage
+    #[serde(default = "default_multiplier")]
+    pub(crate) damage: f32,
+    /// Multiplier of the size
+    #[serde(default = "default_multiplier")]
+    pub(crate) area: f32,
+    /// Extra blades of the sword, spread evenly around the player, or extra daggers of each throw
+    #[serde(default)]
+    pub(crate) amount: u8,
+    /// Radians per second the daggers turn towards the closest ennemy
+    #[serde(default)]
+    pub(crate) homing: f32,
+    /// Effects applied by the hits on top of the ones of the weapon
+    #[serde(default)]
+    pub(crate) effects: Vec<StatusApplication>,
+}
+
+impl EvolutionDefinition {
+    pub(crate) fn tint(&self) -> Color {
+        Color::new(self.tint[0], self.tint[1], self.tint[2], self.tint[3])
+    }
+}
+
+/// Bonus gained by a weapon at each level after the first one
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct WeaponScaling {
+    pub(crate) damage: f32,
+    /// Added to the size multiplier
+    pub(crate) area: f32,
+    /// Added to the speed multiplier
+    pub(crate) speed: f32,
+    /// Fraction of the cooldown removed
+    pub(crate) cooldown: f32,
+    /// Extra projectiles, rounded down
+    pub(crate) amount: f32,
+}
+
+impl WeaponDefinitions {
+    /// Load the weapon definitions, the error naming the faulty field when they are invalid
+    pub(crate) async fn load() -> Result<Self, String> {
+        let definitions: WeaponDefinitions = load_ron(WEAPONS_PATH).await?;
+        definitions.validate().map_err(|error| format!("Invalid {WEAPONS_PATH}: {error}"))?;
+        Ok(definitions)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let sword = &self.sword;
+        validate_hitbox(&sword.hitbox, "sword.hitbox")?;
+        ensure_positive(sword.size_ratio, "sword.size_ratio")?;
+        ensure_non_negative(sword.rotation_speed, "sword.rotation_speed")?;
+        ensure_non_negative(sword.damage, "sword.damage")?;
+        sword.per_level.validate(sword.max_level, "sword")?;
+        sword.on_hit.validate("sword")?;
+
+        let daggers = &self.daggers;
+        validate_hitbox(&daggers.hitbox, "daggers.hitbox")?;
+        ensure_positive(daggers.size_ratio, "daggers.size_ratio")?;
+        ensure_positive(daggers.speed, "daggers.speed")?;
+        ensure_non_negative(daggers.damage, "daggers.damage")?;
+        ensure_positive(daggers.lifetime, "daggers.lifetime")?;
+        ensure_positive(daggers.cooldown, "daggers.cooldown")?;
+        ensure_non_negative(daggers.spread, "daggers.spread")?;
+        daggers.per_level.validate(daggers.max_level, "daggers")?;
+        daggers.on_hit.validate("daggers")?;
+
+        let aura = &self.aura;
+        for (i, component) in aura.color.iter().enumerate() {
+            ensure(*component >= 0. && *component <= 1., &format!("aura.color[{i}]"),
+                &format!("must be between 0 and 1, got {component}"))?;
+        }
+        ensure_positive(aura.radius, "aura.radius")?;
+        ensure_non_negative(aura.damage, "aura.damage")?;
+        aura.per_level.validate(aura.max_level, "aura")?;
+        aura.on_hit.validate("aura")?;
+
+        for (i, evolution) in self.evolutions.iter().enumerate() {
+            let field = format!("evolutions[{i}]");
+            ensure_non_negative(evolution.damage, &format!("{field}.damage"))?;
+            ensure_positive(evolution.area, &format!("{field}.area"))?;
+            ensure_non_negative(evolution.homing, &format!("{field}.homing"))?;
+            validate_statuses(&evolution.effects, &format!("{field}.effects"))?;
+            // Only the first evolution of a weapon could ever happen
+            let duplicate = self.evolutions[..i].iter().any(|other| other.weapon == evolution.weapon);
+            ensure(!duplicate, &format!("{field}.weapon"), &format!("{:?} already has an evolution", evolution.weapon))?;
+        }
+        Ok(())
+    }
+}
+
+impl WeaponScaling {
+    fn validate(&self, max_level: u8, weapon: &str) -> Result<(), String> {
+        ensure(max_level >= 1, &format!("{weapon}.max_level"), "must be at least 1")?;
+        // The weapon can't get worse or lose its whole cooldown by leveling up
+        ensure_non_negative(self.damage, &format!("{weapon}.per_level.damage"))?;
+        ensure_non_negative(self.area, &format!("{weapon}.per_level.area"))?;
+        ensure_non_negative(self.speed, &format!("{weapon}.per_level.speed"))?;
+        ensure_non_negative(self.amount, &format!("{weapon}.per_level.amount"))?;
+        let max_cooldown_reduction = self.cooldown * (max_level - 1) as f32;
+        ensure(self.cooldown >= 0. && max_cooldown_reduction < 1., &format!("{weapon}.per_level.cooldown"),
+            &format!("must be positive and remove less than the whole cooldown at level {max_level}, \
+                removes {max_cooldown_reduction}"))
+    }
+}
+
+impl OnHit {
+    fn validate(&self, weapon: &str) -> Result<(), String> {
+        ensure_non_negative(self.knockback, &format!("{weapon}.on_hit.knockback"))?;
+        validate_statuses(&self.effects, &format!("{weapon}.on_hit.effects"))
+    }
+}
+
+fn default_tint() -> [f32; 4] {
+    [1., 1., 1., 1.]
+}
+
+fn default_multiplier() -> f32 {
+    1.
+}
+
+fn validate_hitbox(hitbox: &WeaponHitboxParams, field: &str) -> Result<(), String> {
+    ensure_positive(hitbox.params.size.x, &format!("{field}.params.size.x"))?;
+    ensure_positive(hitbox.params.size.y, &format!("{field}.params.size.y"))?;
+    ensure_ratio(hitbox.width_ratio, &format!("{field}.width_ratio"))?;
+    ensure_ratio(hitbox.height_ratio, &format!("{field}.height_ratio"))
+}
@@ -0,0 +1,170 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::{asset_manager::{AssetManager, SpriteHandle}, collision::{Collidable, collides}, damage::DamageType, entity::{stats::Stats, status::StatusApplication}, game::GameTextures, weapons::{aura::Aura, dagger::DaggerAggregate, sword::Sword, weapon_definition::{EvolutionDefinition, OnHit, WeaponDefinitions}}};
+
+pub(crate) const MAX_WEAPONS: usize = 6; // Number of weapon slots of the player
+
+/// Every kind of weapon, used to pick a starting weapon or a new weapon on level up
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub(crate) enum WeaponKind {
+    Sword,
+    Daggers,
+    Aura,
+}
+
+impl WeaponKind {
+    pub(crate) const ALL: [WeaponKind; 3] = [WeaponKind::Sword, WeaponKind::Daggers, WeaponKind::Aura];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            WeaponKind::Sword => "Sword",
+            WeaponKind::Daggers => "Daggers",
+            WeaponKind::Aura => "Aura",
+        }
+    }
+}
+
+/// Hit of a weapon of the player on an ennemy
+pub(crate) struct WeaponHit {
+    pub(crate) kind: WeaponKind,
+    pub(crate) damage: f32,
+    pub(crate) damage_type: DamageType,
+    /// Speed in pixels per second at which the ennemy is pushed away from the player
+    pub(crate) knockback: f32,
+    pub(crate) effects: Vec<StatusApplication>,
+}
+
+/// A weapon owned by the player
+pub(crate) enum WeaponSlot {
+    Sword(Sword),
+    Daggers(DaggerAggregate),
+    Aura(Aura),
+}
+
+impl WeaponSlot {
+    pub(crate) fn kind(&self) -> WeaponKind {
+        match self {
+            WeaponSlot::Sword(_) => WeaponKind::Sword,
+            WeaponSlot::Daggers(_) => WeaponKind::Daggers,
+            WeaponSlot::Aura(_) => WeaponKind::Aura,
+        }
+    }
+
+    pub(crate) fn level(&self) -> u8 {
+        match self {
+            WeaponSlot::Sword(sword) => sword.level,
+            WeaponSlot::Daggers(daggers) => daggers.level,
+            WeaponSlot::Aura(aura) => aura.level,
+        }
+    }
+
+    pub(crate) fn max_level(&self) -> u8 {
+        match self {
+            WeaponSlot::Sword(sword) => sword.max_level(),
+            WeaponSlot::Daggers(daggers) => daggers.max_level(),
+            WeaponSlot::Aura(aura) => aura.max_level(),
+        }
+    }
+
+    /// Sprite of the weapon, `None` for the aura which is drawn as a circle
+    pub(crate) fn sprite(&self) -> Option<SpriteHandle> {
+        match self {
+            WeaponSlot::Sword(sword) => Some(sword.sprite()),
+            WeaponSlot::Daggers(daggers) => Some(daggers.sprite()),
+            WeaponSlot::Aura(_) => None,
+        }
+    }
+
+    /// Fraction of the cooldown left before the weapon attacks again, 0 for the weapons attacking all the time
+    pub(crate) fn cooldown_left(&self) -> f32 {
+        match self {
+            WeaponSlot::Daggers(daggers) => daggers.cooldown_left(),
+            WeaponSlot::Sword(_) | WeaponSlot::Aura(_) => 0.,
+        }
+    }
+
+    pub(crate) fn evolution(&self) -> Option<&EvolutionDefinition> {
+        match self {
+            WeaponSlot::Sword(sword) => sword.evolution.as_ref(),
+            WeaponSlot::Daggers(daggers) => daggers.evolution.as_ref(),
+            WeaponSlot::Aura(aura) => aura.evolution.as_ref(),
+        }
+    }
+
+    /// Turn the weapon into its evolution, or back into its base version with `None`
+    pub(crate) fn set_evolution(&mut self, evolution: Option<EvolutionDefinition>) {
+        match self {
+            WeaponSlot::Sword(sword) => sword.evolution = evolution,
+            WeaponSlot::Daggers(daggers) => daggers.evolution = evolution,
+            WeaponSlot::Aura(aura) => aura.evolution = evolution,
+        }
+    }
+
+    pub(crate) fn level_up(&mut self) {
+        match self {
+            WeaponSlot::Sword(sword) => sword.level += 1,
+            WeaponSlot::Daggers(daggers) => daggers.level += 1,
+            WeaponSlot::Aura(aura) => aura.level += 1,
+        }
+    }
+
+    /// Use a reloaded definition. An evolution removed from the definitions is lost.
+    pub(crate) fn apply_definition(&mut self, definitions: &WeaponDefinitions, textures: &GameTextures) {
+        match self {
+            WeaponSlot::Sword(sword) => sword.apply_definition(&definitions.sword, textures.sword),
+            WeaponSlot::Daggers(daggers) => daggers.apply_definition(&definitions.daggers, textures.dagger),
+            WeaponSlot::Aura(aura) => aura.apply_definition(&definitions.aura),
+        }
+        let evolution = self.evolution().and_then(|evolution| definitions.evolutions.iter()
+            .find(|definition| definition.name == evolution.name && definition.weapon == self.kind()));
+        self.set_evolution(evolution.cloned());
+    }
+
+    /// Update the weapon, `position` being the position of the player wielding it and `targets`
+    /// the positions of the ennemies it can aim at
+    pub(crate) fn update(&mut self, position: Vec2, stats: &Stats, targets: &[Vec2]) {
+        match self {
+            WeaponSlot::Sword(sword) => {
+                sword.weapon.world_position = position;
+                sword.update(stats);
+            }
+            WeaponSlot::Daggers(daggers) => daggers.update(targets),
+            WeaponSlot::Aura(aura) => aura.update(position, stats),
+        }
+    }
+
+    pub(crate) fn draw(&self, screen_origin_position: Vec2, assets: &AssetManager) {
+        match self {
+            WeaponSlot::Sword(sword) => sword.draw(screen_origin_position, assets),
+            WeaponSlot::Daggers(daggers) => daggers.draw(screen_origin_position, assets),
+            WeaponSlot::Aura(aura) => aura.draw(screen_origin_position),
+        }
+    }
+
+    fn on_hit(&self) -> &OnHit {
+        match self {
+            WeaponSlot::Sword(sword) => sword.on_hit(),
+            WeaponSlot::Daggers(daggers) => daggers.on_hit(),
+            WeaponSlot::Aura(aura) => aura.on_hit(),
+        }
+    }
+
+    /// Hit of the weapon on the target, with the effects of its evolution. `None` when it doesn't touch it.
+    pub(crate) fn hit(&mut self, target: &impl Collidable, stats: &Stats) -> Option<WeaponHit> {
+        let (touches, damage) = match self {
+            WeaponSlot::Sword(sword) => (sword.hits(target), sword.damage(stats)),
+            WeaponSlot::Daggers(daggers) => (daggers.collide_with(target), daggers.damage(stats)),
+            WeaponSlot::Aura(aura) => (collides(aura, target), aura.damage(stats)),
+        };
+        if !touches {
+            return None;
+        }
+        let on_hit = self.on_hit();
+        let mut effects = on_hit.effects.clone();
+        if let Some(evolution) = self.evolution() {
+            effects.extend(&evolution.effects);
+        }
+        Some(WeaponHit { kind: self.kind(), damage, damage_type: on_hit.damage_type, knockback: on_hit.knockback, effects })
+    }
+}